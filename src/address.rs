@@ -1,7 +1,9 @@
-use crate::utils::base58_encode;
-use crate::hash::{hash160, double_sha256};
-use crate::utils::encode_bech32;
+use crate::utils::{base58check_encode, Base58Error};
+use crate::utils::base58::base58check_decode_len;
+use crate::hash::hash160;
+use crate::utils::{encode_bech32, decode_bech32};
 use crate::utils::bech32::{convert_bits, Bech32Variant};
+use crate::error::Error;
 
 pub fn p2pkh_address(pubkey: &[u8]) -> String {
     let hash160_val = hash160(pubkey);
@@ -10,12 +12,15 @@ pub fn p2pkh_address(pubkey: &[u8]) -> String {
     payload.push(0x00);
     payload.extend_from_slice(&hash160_val);
 
-    let check = double_sha256(&payload);
-    let checksum = &check[..4];
+    base58check_encode(&payload)
+}
 
-    payload.extend_from_slice(checksum);
+pub fn decode_base58_address(address: &str) -> Result<(u8, [u8; 20]), Base58Error> {
+    let payload = base58check_decode_len(address, 21)?;
 
-    base58_encode(&payload)
+    let mut hash = [0u8; 20];
+    hash.copy_from_slice(&payload[1..]);
+    Ok((payload[0], hash))
 }
 
 pub fn p2sh_p2wpkh_address(pubkey: &[u8]) -> String {
@@ -31,17 +36,144 @@ pub fn p2sh_p2wpkh_address(pubkey: &[u8]) -> String {
     let mut payload = Vec::with_capacity(1 + 20);
     payload.push(0x05);
     payload.extend_from_slice(&redeem_script_hash);
-    let checksum = &double_sha256(&payload)[..4];
-    payload.extend_from_slice(checksum);
 
-    base58_encode(&payload)
+    base58check_encode(&payload)
 }
 
-pub fn p2wpkh_address(pubkey: &[u8]) -> String {
+pub fn p2wpkh_address(pubkey: &[u8]) -> Result<String, Error> {
     let ripemd160_hash = hash160(pubkey);
 
-    let mut data = vec![0x00];
-    data.extend_from_slice(&convert_bits(&ripemd160_hash, 8, 5, true).unwrap());
+    encode_segwit_address("tb", 0, &ripemd160_hash)
+}
+
+fn check_witness_program(version: u8, program: &[u8]) -> Result<(), Error> {
+    if version > 16 {
+        return Err(Error::Address("Invalid witness version"));
+    }
+    if program.len() < 2 || program.len() > 40 {
+        return Err(Error::Address("Invalid witness program length"));
+    }
+    if version == 0 && program.len() != 20 && program.len() != 32 {
+        return Err(Error::Address("Invalid witness program length for witness version 0"));
+    }
+    Ok(())
+}
+
+fn witness_variant(version: u8) -> Bech32Variant {
+    if version == 0 {
+        Bech32Variant::Bech32
+    } else {
+        Bech32Variant::Bech32m
+    }
+}
+
+pub fn encode_segwit_address(hrp: &str, version: u8, program: &[u8]) -> Result<String, Error> {
+    check_witness_program(version, program)?;
+
+    let mut data = vec![version];
+    data.extend_from_slice(&convert_bits(program, 8, 5, true)?);
+
+    encode_bech32(hrp, &data, witness_variant(version))
+}
+
+pub fn encode_segwit_address_upper(hrp: &str, version: u8, program: &[u8]) -> Result<String, Error> {
+    encode_segwit_address(hrp, version, program).map(|address| address.to_ascii_uppercase())
+}
+
+pub fn decode_segwit_address(address: &str) -> Result<(String, u8, Vec<u8>), Error> {
+    let (hrp, data, variant) = decode_bech32(address)?;
+    if data.is_empty() {
+        return Err(Error::Address("Empty witness program"));
+    }
+
+    let version = data[0];
+    let program = convert_bits(&data[1..], 5, 8, false)?;
+    check_witness_program(version, &program)?;
 
-    encode_bech32("tb", &data, Bech32Variant::Bech32)
-}
\ No newline at end of file
+    if variant != witness_variant(version) {
+        return Err(Error::Address("Invalid checksum variant for witness version"));
+    }
+
+    Ok((hrp, version, program))
+}
+
+pub fn segwit_script_pubkey(version: u8, program: &[u8]) -> Result<Vec<u8>, Error> {
+    check_witness_program(version, program)?;
+
+    let mut script_pubkey = Vec::with_capacity(2 + program.len());
+    script_pubkey.push(if version == 0 { 0x00 } else { 0x50 + version });
+    script_pubkey.push(program.len() as u8);
+    script_pubkey.extend_from_slice(program);
+    Ok(script_pubkey)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // BIP350 valid segwit addresses and their scriptPubKeys.
+    const VALID: [(&str, &str); 8] = [
+        ("BC1QW508D6QEJXTDG4Y5R3ZARVARY0C5XW7KV8F3T4", "0014751e76e8199196d454941c45d1b3a323f1433bd6"),
+        ("tb1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q0sl5k7", "00201863143c14c5166804bd19203356da136c985678cd4d27a1b8c6329604903262"),
+        ("bc1pw508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kt5nd6y", "5128751e76e8199196d454941c45d1b3a323f1433bd6751e76e8199196d454941c45d1b3a323f1433bd6"),
+        ("BC1SW50QGDZ25J", "6002751e"),
+        ("bc1zw508d6qejxtdg4y5r3zarvaryvaxxpcs", "5210751e76e8199196d454941c45d1b3a323"),
+        ("tb1qqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesrxh6hy", "0020000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"),
+        ("tb1pqqqqp399et2xygdj5xreqhjjvcmzhxw4aywxecjdzew6hylgvsesf3hn0c", "5120000000c4a5cad46221b2a187905e5266362b99d5e91c6ce24d165dab93e86433"),
+        ("bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0", "512079be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798"),
+    ];
+
+    #[test]
+    fn bip350_valid_addresses() {
+        for (address, script_pubkey) in VALID {
+            let (hrp, version, program) = decode_segwit_address(address).unwrap();
+            assert_eq!(vec_to_hex(&segwit_script_pubkey(version, &program).unwrap()), script_pubkey);
+
+            let lower = address.to_lowercase();
+            assert_eq!(encode_segwit_address(&hrp, version, &program).unwrap(), lower);
+            assert_eq!(encode_segwit_address_upper(&hrp, version, &program).unwrap(), lower.to_uppercase());
+        }
+    }
+
+    #[test]
+    fn bip350_invalid_addresses() {
+        // BIP173 and BIP350 invalid segwit addresses.
+        let invalid = [
+            "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqh2y7hd",
+            "tb1z0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqglt7rf",
+            "bc1qr508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+            "tb1q0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq24jc47",
+            "bc1zw508d6qejxtdg4y5r3zarvaryvqyzf3du",
+            "bc1rw5uspcuh",
+            "bc10w508d6qejxtdg4y5r3zarvary0c5xw7kw508d6qejxtdg4y5r3zarvary0c5xw7kw5rljs90",
+            "bc1pw5dgrnzv",
+            "bc1p38j9r5y49hruaue7wxjce0updqjuyyx0kh56v8s25huc6995vvpql3jow4",
+            "bc1gmk9yu",
+        ];
+        for address in invalid {
+            assert!(decode_segwit_address(address).is_err(), "{}", address);
+        }
+    }
+
+    #[test]
+    fn witness_program_rules() {
+        assert!(encode_segwit_address("bc", 0, &[0u8; 21]).is_err());
+        assert!(encode_segwit_address("bc", 0, &[0u8; 32]).is_ok());
+        assert!(encode_segwit_address("bc", 1, &[0u8; 1]).is_err());
+        assert!(encode_segwit_address("bc", 1, &[0u8; 41]).is_err());
+        assert!(encode_segwit_address("bc", 16, &[0u8; 40]).is_ok());
+        assert!(encode_segwit_address("bc", 17, &[0u8; 20]).is_err());
+
+        assert!(segwit_script_pubkey(0, &[0u8; 21]).is_err());
+        assert!(segwit_script_pubkey(1, &[0u8; 41]).is_err());
+        assert!(segwit_script_pubkey(17, &[0u8; 20]).is_err());
+        assert_eq!(vec_to_hex(&segwit_script_pubkey(16, &[0u8; 2]).unwrap()), "60020000");
+    }
+
+    #[test]
+    fn p2wpkh_matches_bip173() {
+        let pubkey = crate::utils::hex_to_vec("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798").unwrap();
+        assert_eq!(p2wpkh_address(&pubkey).unwrap(), "tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx");
+    }
+}
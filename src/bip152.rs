@@ -0,0 +1,65 @@
+use crate::hash::{sha256, siphash24, Wtxid};
+use crate::tx::{Tx, calculate_wtxid};
+
+pub fn short_id_keys(header: &[u8; 80], nonce: u64) -> (u64, u64) {
+    let mut data = Vec::with_capacity(80 + 8);
+    data.extend_from_slice(header);
+    data.extend_from_slice(&nonce.to_le_bytes());
+
+    let hash = sha256(&data);
+    let mut k0 = [0u8; 8];
+    let mut k1 = [0u8; 8];
+    k0.copy_from_slice(&hash[0..8]);
+    k1.copy_from_slice(&hash[8..16]);
+    (u64::from_le_bytes(k0), u64::from_le_bytes(k1))
+}
+
+pub fn short_id(k0: u64, k1: u64, wtxid: &Wtxid) -> [u8; 6] {
+    let hash = siphash24(k0, k1, wtxid.as_byte_array());
+
+    let mut id = [0u8; 6];
+    id.copy_from_slice(&hash.to_le_bytes()[..6]);
+    id
+}
+
+pub fn tx_short_id(header: &[u8; 80], nonce: u64, tx: &Tx) -> [u8; 6] {
+    let (k0, k1) = short_id_keys(header, nonce);
+    short_id(k0, k1, &calculate_wtxid(tx))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::deserialize_transaction;
+    use crate::utils::vec_to_hex;
+
+    const GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
+
+    fn header() -> [u8; 80] {
+        std::array::from_fn(|i| i as u8)
+    }
+
+    #[test]
+    fn keys_from_header_and_nonce() {
+        let nonce = 0x0123456789abcdef;
+        let mut data = header().to_vec();
+        data.extend_from_slice(&[0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01]);
+        let hash = sha256(&data);
+
+        let (k0, k1) = short_id_keys(&header(), nonce);
+        assert_eq!(k0.to_le_bytes(), hash[0..8]);
+        assert_eq!(k1.to_le_bytes(), hash[8..16]);
+        assert_eq!((k0, k1), (0x58c8bfd817f6b135, 0xbf0d87f75288eb6e));
+    }
+
+    #[test]
+    fn short_id_keeps_low_six_bytes() {
+        let tx = deserialize_transaction(GENESIS_COINBASE).unwrap();
+        let (k0, k1) = short_id_keys(&header(), 0x0123456789abcdef);
+        let full = siphash24(k0, k1, calculate_wtxid(&tx).as_byte_array());
+
+        let id = tx_short_id(&header(), 0x0123456789abcdef, &tx);
+        assert_eq!(id, full.to_le_bytes()[..6]);
+        assert_eq!(vec_to_hex(&id), "dd5467635a61");
+    }
+}
@@ -1,15 +1,47 @@
 use secp256k1::{Secp256k1, SecretKey, PublicKey, Scalar};
-use crate::hash::{hmac_sha512, double_sha256, hash160};
+use crate::hash::{hmac_sha512, hash160};
+use crate::utils::base58::{base58_encode, base58_checksum, base58check_decode_len, Base58Error};
 use crate::bip39::generate_seed;
+use crate::error::Error;
 
-pub fn generate_master_prv(mnemonic: &str, passphrase: &str, version: &[u8; 4]) -> Vec<u8> {
+const HARDENED_INDEX: u32 = 0x80000000;
+
+fn extended_key_data(key: &[u8]) -> Result<&[u8], Error> {
+    if key.len() != 82 {
+        return Err(Error::Bip32("Extended key must be 82 bytes"));
+    }
+    let (data, checksum) = key.split_at(78);
+    if base58_checksum(data) != checksum {
+        return Err(Error::Bip32("Invalid extended key checksum"));
+    }
+    Ok(data)
+}
+
+fn private_key_data(key: &[u8]) -> Result<&[u8], Error> {
+    let data = extended_key_data(key)?;
+    if data[45] != 0x00 {
+        return Err(Error::Bip32("Not an extended private key"));
+    }
+    Ok(data)
+}
+
+pub fn generate_master_prv(mnemonic: &str, passphrase: &str, version: &[u8; 4]) -> Result<Vec<u8>, Error> {
     let seed = generate_seed(mnemonic, passphrase);
+    master_prv_from_seed(&seed, version)
+}
+
+pub fn master_prv_from_seed(seed: &[u8], version: &[u8; 4]) -> Result<Vec<u8>, Error> {
+    if seed.len() < 16 || seed.len() > 64 {
+        return Err(Error::Bip32("Seed must be between 16 and 64 bytes"));
+    }
 
     let hmac_key = b"Bitcoin seed";
-    let hmac_result = hmac_sha512(hmac_key, &seed);
+    let hmac_result = hmac_sha512(hmac_key, seed);
 
     let master_private_key = &hmac_result[..32];
     let master_chain_code = &hmac_result[32..];
+    SecretKey::from_slice(master_private_key)
+        .map_err(|_| Error::Bip32("Invalid master key, use another seed"))?;
 
     let mut extended_key = Vec::new();
 
@@ -20,21 +52,21 @@ pub fn generate_master_prv(mnemonic: &str, passphrase: &str, version: &[u8; 4])
     extended_key.extend_from_slice(master_chain_code);
     extended_key.push(0x00);
     extended_key.extend_from_slice(master_private_key);
-    let checksum = &double_sha256(&extended_key)[..4];
-    extended_key.extend_from_slice(checksum);
+    let checksum = base58_checksum(&extended_key);
+    extended_key.extend_from_slice(&checksum);
 
-    extended_key
+    Ok(extended_key)
 }
 
-pub fn prv_to_pub(prv: &[u8], version: &[u8; 4]) -> Vec<u8> {
-    let ext_data = &prv[..78];
+pub fn prv_to_pub(prv: &[u8], version: &[u8; 4]) -> Result<Vec<u8>, Error> {
+    let ext_data = private_key_data(prv)?;
 
     let chain_code = &ext_data[13..45];
     let key_data = &ext_data[45..78];
     let priv_key_bytes = &key_data[1..];
 
     let secp = Secp256k1::new();
-    let sk = SecretKey::from_slice(priv_key_bytes).expect("Invalid private key");
+    let sk = SecretKey::from_slice(priv_key_bytes)?;
     let pk = PublicKey::from_secret_key(&secp, &sk);
     let pk_serialized = pk.serialize();
 
@@ -45,29 +77,29 @@ pub fn prv_to_pub(prv: &[u8], version: &[u8; 4]) -> Vec<u8> {
     extended_pub.extend_from_slice(&ext_data[9..13]);
     extended_pub.extend_from_slice(chain_code);
     extended_pub.extend_from_slice(&pk_serialized);
-    let checksum = &double_sha256(&extended_pub)[..4];
-    extended_pub.extend_from_slice(checksum);
+    let checksum = base58_checksum(&extended_pub);
+    extended_pub.extend_from_slice(&checksum);
 
-    extended_pub
+    Ok(extended_pub)
 }
 
-pub fn derive_child_prv(parent_prv: &[u8], index: u32, version: &[u8; 4]) -> Vec<u8> {
-    assert!(parent_prv.len() == 82, "Invalid parent xprv length");
-    let parent_data = &parent_prv[..78];
+pub fn derive_child_prv(parent_prv: &[u8], index: u32, version: &[u8; 4]) -> Result<Vec<u8>, Error> {
+    let parent_data = private_key_data(parent_prv)?;
 
-    let parent_depth = parent_data[4];
+    let child_depth = parent_data[4]
+        .checked_add(1)
+        .ok_or(Error::Bip32("Maximum derivation depth exceeded"))?;
     let parent_chain_code = &parent_data[13..45];
     let parent_privkey_bytes = &parent_data[46..78];
 
     let secp = Secp256k1::new();
-    let parent_sk = SecretKey::from_slice(parent_privkey_bytes)
-        .expect("Invalid parent private key");
+    let parent_sk = SecretKey::from_slice(parent_privkey_bytes)?;
     let parent_pk = PublicKey::from_secret_key(&secp, &parent_sk);
     let parent_pk_ser = parent_pk.serialize();
     let parent_fingerprint = &hash160(&parent_pk_ser)[..4];
 
     let mut data = Vec::with_capacity(1 + 32 + 4);
-    if index >= 0x80000000 {
+    if index >= HARDENED_INDEX {
         data.push(0x00);
         data.extend_from_slice(parent_privkey_bytes);
     } else {
@@ -76,41 +108,42 @@ pub fn derive_child_prv(parent_prv: &[u8], index: u32, version: &[u8; 4]) -> Vec
     data.extend_from_slice(&index.to_be_bytes());
 
     let i = hmac_sha512(parent_chain_code, &data);
-    let il = &i[..32];
+    let mut il = [0u8; 32];
+    il.copy_from_slice(&i[..32]);
     let ir = &i[32..];
 
-    let il_array: [u8; 32] = il.try_into().expect("il must be 32 bytes");
-
-    let tweak_scalar = Scalar::from_be_bytes(il_array)
-        .expect("Impossible to convert tweak scalar");
+    let tweak_scalar = Scalar::from_be_bytes(il)
+        .map_err(|_| Error::Bip32("Invalid child key, proceed with the next index"))?;
 
     let child_sk = parent_sk
         .add_tweak(&tweak_scalar)
-        .expect("Derivation failed");
+        .map_err(|_| Error::Bip32("Invalid child key, proceed with the next index"))?;
 
     let mut child_extended = Vec::with_capacity(78);
     child_extended.extend_from_slice(version);
-    child_extended.push(parent_depth.wrapping_add(1));
+    child_extended.push(child_depth);
     child_extended.extend_from_slice(parent_fingerprint);
     child_extended.extend_from_slice(&index.to_be_bytes());
     child_extended.extend_from_slice(ir);
     child_extended.push(0x00);
     child_extended.extend_from_slice(&child_sk[..]);
 
-    let checksum = &double_sha256(&child_extended)[..4];
-    child_extended.extend_from_slice(checksum);
+    let checksum = base58_checksum(&child_extended);
+    child_extended.extend_from_slice(&checksum);
 
-    child_extended
+    Ok(child_extended)
 }
 
+pub fn derive_child_pub(parent_pub: &[u8], index: u32, version: &[u8; 4]) -> Result<Vec<u8>, Error> {
+    if index >= HARDENED_INDEX {
+        return Err(Error::Bip32("Impossible to derive hardened child from public key"));
+    }
 
-pub fn derive_child_pub(parent_pub: &[u8], index: u32, version: &[u8; 4]) -> Vec<u8> {
-    assert!(index < 0x80000000, "Impossible to derive hardened child from public key");
-    assert!(parent_pub.len() == 82, "xpub length must be 82 bytes");
-
-    let parent_data = &parent_pub[..78];
+    let parent_data = extended_key_data(parent_pub)?;
 
-    let parent_depth = parent_data[4];
+    let child_depth = parent_data[4]
+        .checked_add(1)
+        .ok_or(Error::Bip32("Maximum derivation depth exceeded"))?;
     let parent_chain_code = &parent_data[13..45];
     let parent_pk_ser = &parent_data[45..78];
 
@@ -126,36 +159,134 @@ pub fn derive_child_pub(parent_pub: &[u8], index: u32, version: &[u8; 4]) -> Vec
 
     let secp = Secp256k1::new();
     let il_sk = SecretKey::from_slice(il)
-        .expect("il is not a valid private key");
+        .map_err(|_| Error::Bip32("Invalid child key, proceed with the next index"))?;
     let il_pk = PublicKey::from_secret_key(&secp, &il_sk);
-    let parent_pk = PublicKey::from_slice(parent_pk_ser)
-        .expect("Impossible to convert parent public key");
+    let parent_pk = PublicKey::from_slice(parent_pk_ser)?;
     let child_pk = parent_pk.combine(&il_pk)
-        .expect("Impossible to combine public keys");
+        .map_err(|_| Error::Bip32("Invalid child key, proceed with the next index"))?;
     let child_pk_ser = child_pk.serialize();
 
     let mut child_extended = Vec::with_capacity(78);
     child_extended.extend_from_slice(version);
-    child_extended.push(parent_depth.wrapping_add(1));
+    child_extended.push(child_depth);
     child_extended.extend_from_slice(parent_fingerprint);
     child_extended.extend_from_slice(&index.to_be_bytes());
     child_extended.extend_from_slice(ir);
     child_extended.extend_from_slice(&child_pk_ser);
 
-    let checksum = &double_sha256(&child_extended)[..4];
-    child_extended.extend_from_slice(checksum);
+    let checksum = base58_checksum(&child_extended);
+    child_extended.extend_from_slice(&checksum);
+
+    Ok(child_extended)
+}
 
-    child_extended
+pub fn extended_key_to_string(key: &[u8]) -> String {
+    base58_encode(key)
 }
 
-pub fn prv_to_secret_key(xprv: &[u8]) -> SecretKey {
-    let key_data = &xprv[46..78];
-    SecretKey::from_slice(key_data)
-        .expect("key_data must be 32 bytes and valid private key")
+pub fn extended_key_from_string(encoded: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut key = base58check_decode_len(encoded, 78)?;
+    let checksum = base58_checksum(&key);
+    key.extend_from_slice(&checksum);
+    Ok(key)
 }
 
-pub fn pub_to_public_key(xpub: &[u8]) -> PublicKey {
-    let key_data = &xpub[45..78];
-    PublicKey::from_slice(key_data)
-        .expect("key_data must be 33 bytes and valid pub key")
-}
\ No newline at end of file
+pub fn prv_to_secret_key(xprv: &[u8]) -> Result<SecretKey, Error> {
+    let key_data = &private_key_data(xprv)?[46..78];
+    Ok(SecretKey::from_slice(key_data)?)
+}
+
+pub fn pub_to_public_key(xpub: &[u8]) -> Result<PublicKey, Error> {
+    let key_data = &extended_key_data(xpub)?[45..78];
+    Ok(PublicKey::from_slice(key_data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::hex_to_vec;
+
+    const XPRV: [u8; 4] = [0x04, 0x88, 0xad, 0xe4];
+    const XPUB: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+
+    fn master() -> Vec<u8> {
+        let seed = hex_to_vec("000102030405060708090a0b0c0d0e0f").unwrap();
+        master_prv_from_seed(&seed, &XPRV).unwrap()
+    }
+
+    fn with_checksum(mut data: Vec<u8>) -> Vec<u8> {
+        data.truncate(78);
+        let checksum = base58_checksum(&data);
+        data.extend_from_slice(&checksum);
+        data
+    }
+
+    // BIP32 test vector 1.
+    #[test]
+    fn bip32_vector_1() {
+        let master = master();
+        assert_eq!(extended_key_to_string(&master), "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi");
+        assert_eq!(
+            extended_key_to_string(&prv_to_pub(&master, &XPUB).unwrap()),
+            "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"
+        );
+
+        let child = derive_child_prv(&master, HARDENED_INDEX, &XPRV).unwrap();
+        assert_eq!(extended_key_to_string(&child), "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7");
+        let child_pub = prv_to_pub(&child, &XPUB).unwrap();
+        assert_eq!(extended_key_to_string(&child_pub), "xpub68Gmy5EdvgibQVfPdqkBBCHxA5htiqg55crXYuXoQRKfDBFA1WEjWgP6LHhwBZeNK1VTsfTFUHCdrfp1bgwQ9xv5ski8PX9rL2dZXvgGDnw");
+
+        let grandchild_pub = derive_child_pub(&child_pub, 1, &XPUB).unwrap();
+        assert_eq!(extended_key_to_string(&grandchild_pub), "xpub6ASuArnXKPbfEwhqN6e3mwBcDTgzisQN1wXN9BJcM47sSikHjJf3UFHKkNAWbWMiGj7Wf5uMash7SyYq527Hqck2AxYysAA7xmALppuCkwQ");
+        let grandchild = derive_child_prv(&child, 1, &XPRV).unwrap();
+        assert_eq!(prv_to_pub(&grandchild, &XPUB).unwrap(), grandchild_pub);
+
+        let decoded = extended_key_from_string(&extended_key_to_string(&grandchild)).unwrap();
+        assert_eq!(decoded, grandchild);
+    }
+
+    #[test]
+    fn master_key_skips_mnemonic_validation() {
+        let mnemonic = "not a bip39 mnemonic";
+        let expected = master_prv_from_seed(&generate_seed(mnemonic, "TREZOR"), &XPRV).unwrap();
+        assert_eq!(generate_master_prv(mnemonic, "TREZOR", &XPRV).unwrap(), expected);
+        assert!(master_prv_from_seed(&[0u8; 15], &XPRV).is_err());
+        assert!(master_prv_from_seed(&[0u8; 65], &XPRV).is_err());
+    }
+
+    #[test]
+    fn rejects_malformed_extended_keys() {
+        let master = master();
+        let xpub = prv_to_pub(&master, &XPUB).unwrap();
+
+        let short = &master[..81];
+        assert!(matches!(derive_child_prv(short, 0, &XPRV), Err(Error::Bip32("Extended key must be 82 bytes"))));
+        assert!(matches!(prv_to_pub(short, &XPUB), Err(Error::Bip32("Extended key must be 82 bytes"))));
+
+        let mut corrupted = master.clone();
+        corrupted[81] ^= 1;
+        assert!(matches!(derive_child_prv(&corrupted, 0, &XPRV), Err(Error::Bip32("Invalid extended key checksum"))));
+        assert!(matches!(prv_to_pub(&corrupted, &XPUB), Err(Error::Bip32("Invalid extended key checksum"))));
+
+        assert!(matches!(derive_child_prv(&xpub, 0, &XPRV), Err(Error::Bip32("Not an extended private key"))));
+        assert!(matches!(
+            derive_child_pub(&xpub, HARDENED_INDEX, &XPUB),
+            Err(Error::Bip32("Impossible to derive hardened child from public key"))
+        ));
+    }
+
+    #[test]
+    fn rejects_derivation_past_maximum_depth() {
+        let mut deepest = master();
+        deepest[4] = 255;
+        let deepest = with_checksum(deepest);
+        let deepest_pub = prv_to_pub(&deepest, &XPUB).unwrap();
+        assert!(matches!(derive_child_prv(&deepest, 0, &XPRV), Err(Error::Bip32("Maximum derivation depth exceeded"))));
+        assert!(matches!(derive_child_pub(&deepest_pub, 0, &XPUB), Err(Error::Bip32("Maximum derivation depth exceeded"))));
+
+        let mut parent = master();
+        parent[4] = 254;
+        let child = derive_child_prv(&with_checksum(parent), 0, &XPRV).unwrap();
+        assert_eq!(child[4], 255);
+    }
+}
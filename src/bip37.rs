@@ -0,0 +1,349 @@
+use crate::address::{decode_base58_address, decode_segwit_address};
+use crate::hash::{hash160, murmur3_32};
+use crate::consensus::{serialize, Decodable};
+use crate::tx::{OutPoint, Tx, calculate_txid};
+use crate::utils::{write_varint, read_varint};
+use crate::error::Error;
+
+pub const MAX_BLOOM_FILTER_SIZE: usize = 36_000;
+pub const MAX_HASH_FUNCS: u32 = 50;
+
+const LN2: f64 = std::f64::consts::LN_2;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BloomUpdate {
+    None = 0,
+    All = 1,
+    P2PubkeyOnly = 2,
+}
+
+impl BloomUpdate {
+    pub fn from_u8(flags: u8) -> Result<Self, Error> {
+        match flags & 0x03 {
+            0 => Ok(BloomUpdate::None),
+            1 => Ok(BloomUpdate::All),
+            2 => Ok(BloomUpdate::P2PubkeyOnly),
+            _ => Err(Error::Bip37("Invalid bloom update flags")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    data: Vec<u8>,
+    pub hash_funcs: u32,
+    pub tweak: u32,
+    pub flags: BloomUpdate,
+}
+
+fn script_pushes(script: &[u8]) -> Vec<&[u8]> {
+    let mut pushes = Vec::new();
+    let mut index = 0;
+
+    while index < script.len() {
+        let opcode = script[index];
+        index += 1;
+
+        let len = match opcode {
+            0x01..=0x4b => opcode as usize,
+            0x4c if index < script.len() => {
+                index += 1;
+                script[index - 1] as usize
+            }
+            0x4d if index + 2 <= script.len() => {
+                index += 2;
+                u16::from_le_bytes([script[index - 2], script[index - 1]]) as usize
+            }
+            0x4e if index + 4 <= script.len() => {
+                index += 4;
+                u32::from_le_bytes([script[index - 4], script[index - 3], script[index - 2], script[index - 1]]) as usize
+            }
+            0x4c..=0x4e => break,
+            _ => continue,
+        };
+
+        if index + len > script.len() {
+            break;
+        }
+        pushes.push(&script[index..index + len]);
+        index += len;
+    }
+
+    pushes
+}
+
+fn is_pay_to_pubkey(script: &[u8]) -> bool {
+    (script.len() == 35 && script[0] == 33 && script[34] == 0xac)
+        || (script.len() == 67 && script[0] == 65 && script[66] == 0xac)
+}
+
+fn is_bare_multisig(script: &[u8]) -> bool {
+    script.len() >= 3
+        && (0x51..=0x60).contains(&script[0])
+        && (0x51..=0x60).contains(&script[script.len() - 2])
+        && script[script.len() - 1] == 0xae
+}
+
+impl BloomFilter {
+    pub fn new(elements: usize, fp_rate: f64, tweak: u32, flags: BloomUpdate) -> Self {
+        let elements = elements.max(1) as f64;
+
+        let size_bytes = (-1.0 / (LN2 * LN2) * elements * fp_rate.ln() / 8.0) as usize;
+        let size_bytes = size_bytes.clamp(1, MAX_BLOOM_FILTER_SIZE);
+
+        let hash_funcs = ((size_bytes * 8) as f64 / elements * LN2) as u32;
+        let hash_funcs = hash_funcs.clamp(1, MAX_HASH_FUNCS);
+
+        BloomFilter {
+            data: vec![0u8; size_bytes],
+            hash_funcs,
+            tweak,
+            flags,
+        }
+    }
+
+    fn bit_index(&self, hash_num: u32, data: &[u8]) -> usize {
+        let seed = hash_num.wrapping_mul(0xfba4c795).wrapping_add(self.tweak);
+        murmur3_32(seed, data) as usize % (self.data.len() * 8)
+    }
+
+    pub fn insert(&mut self, data: &[u8]) {
+        if self.data.is_empty() {
+            return;
+        }
+        for i in 0..self.hash_funcs {
+            let bit = self.bit_index(i, data);
+            self.data[bit >> 3] |= 1 << (bit & 7);
+        }
+    }
+
+    pub fn contains(&self, data: &[u8]) -> bool {
+        if self.data.is_empty() {
+            return false;
+        }
+        (0..self.hash_funcs).all(|i| {
+            let bit = self.bit_index(i, data);
+            self.data[bit >> 3] & (1 << (bit & 7)) != 0
+        })
+    }
+
+    pub fn insert_pubkey(&mut self, pubkey: &[u8]) {
+        self.insert(pubkey);
+        self.insert(&hash160(pubkey));
+    }
+
+    pub fn insert_outpoint(&mut self, outpoint: &OutPoint) {
+        self.insert(&serialize(outpoint));
+    }
+
+    pub fn contains_outpoint(&self, outpoint: &OutPoint) -> bool {
+        self.contains(&serialize(outpoint))
+    }
+
+    pub fn insert_address(&mut self, address: &str) -> Result<(), Error> {
+        if let Ok((_, hash)) = decode_base58_address(address) {
+            self.insert(&hash);
+            return Ok(());
+        }
+
+        let (_, _, program) = decode_segwit_address(address)?;
+        self.insert(&program);
+        Ok(())
+    }
+
+    pub fn is_relevant_and_update(&mut self, tx: &Tx) -> bool {
+        let txid = calculate_txid(tx);
+        let mut found = self.contains(txid.as_byte_array());
+
+        for (vout, output) in tx.outputs.iter().enumerate() {
+            let matched = script_pushes(&output.script_pubkey)
+                .iter()
+                .any(|push| !push.is_empty() && self.contains(push));
+            if !matched {
+                continue;
+            }
+            found = true;
+
+            let outpoint = OutPoint { txid, vout: vout as u32 };
+            match self.flags {
+                BloomUpdate::All => self.insert_outpoint(&outpoint),
+                BloomUpdate::P2PubkeyOnly
+                    if is_pay_to_pubkey(&output.script_pubkey) || is_bare_multisig(&output.script_pubkey) =>
+                {
+                    self.insert_outpoint(&outpoint)
+                }
+                _ => {}
+            }
+        }
+
+        if found {
+            return true;
+        }
+
+        tx.inputs.iter().any(|input| {
+            self.contains_outpoint(&input.previous_output)
+                || script_pushes(&input.script_sig)
+                    .iter()
+                    .any(|push| !push.is_empty() && self.contains(push))
+        })
+    }
+
+    pub fn serialize(&self) -> Vec<u8> {
+        let mut raw = Vec::with_capacity(self.data.len() + 12);
+        write_varint(self.data.len(), &mut raw);
+        raw.extend_from_slice(&self.data);
+        raw.extend_from_slice(&self.hash_funcs.to_le_bytes());
+        raw.extend_from_slice(&self.tweak.to_le_bytes());
+        raw.push(self.flags as u8);
+        raw
+    }
+
+    pub fn deserialize(raw: &[u8]) -> Result<Self, Error> {
+        if raw.is_empty() {
+            return Err(Error::Bip37("Empty filterload payload"));
+        }
+
+        let mut index = 0;
+        let len = read_varint(raw, &mut index)?;
+        if len > MAX_BLOOM_FILTER_SIZE {
+            return Err(Error::Bip37("Bloom filter too large"));
+        }
+        if raw.len() != index + len + 9 {
+            return Err(Error::Bip37("Invalid filterload payload length"));
+        }
+
+        let data = raw[index..index + len].to_vec();
+        let mut reader = &raw[index + len..];
+        let hash_funcs = u32::consensus_decode(&mut reader)?;
+        let tweak = u32::consensus_decode(&mut reader)?;
+        let flags = BloomUpdate::from_u8(u8::consensus_decode(&mut reader)?)?;
+
+        if hash_funcs > MAX_HASH_FUNCS {
+            return Err(Error::Bip37("Too many bloom hash functions"));
+        }
+
+        Ok(BloomFilter { data, hash_funcs, tweak, flags })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::Txid;
+    use crate::tx::{TxIn, TxOut};
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    const PUBKEY: &str = "0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798";
+
+    // Bitcoin Core bloom_tests bloom_create_insert_serialize(_with_tweak).
+    #[test]
+    fn core_create_insert_serialize() {
+        for (tweak, expected) in [(0, "03614e9b050000000000000001"), (2147483649, "03ce4299050000000100008001")] {
+            let mut filter = BloomFilter::new(3, 0.01, tweak, BloomUpdate::All);
+
+            let first = hex_to_vec("99108ad8ed9bb6274d3980bab5a85c048f0950c8").unwrap();
+            filter.insert(&first);
+            assert!(filter.contains(&first));
+            assert!(!filter.contains(&hex_to_vec("19108ad8ed9bb6274d3980bab5a85c048f0950c8").unwrap()));
+
+            filter.insert(&hex_to_vec("b5a2c786d9ef4658287ced5914b37a1b4aa32eee").unwrap());
+            filter.insert(&hex_to_vec("b9300670b4c5366e95b2699e8b18bc75e5f729c5").unwrap());
+            assert_eq!(vec_to_hex(&filter.serialize()), expected);
+        }
+    }
+
+    fn funding_tx(pubkey: &[u8]) -> Tx {
+        let mut p2pk = vec![0x21];
+        p2pk.extend_from_slice(pubkey);
+        p2pk.push(0xac);
+
+        let mut p2pkh = vec![0x76, 0xa9, 0x14];
+        p2pkh.extend_from_slice(&hash160(pubkey));
+        p2pkh.extend_from_slice(&[0x88, 0xac]);
+
+        Tx {
+            version: 1,
+            flag: None,
+            inputs: vec![TxIn {
+                previous_output: OutPoint { txid: Txid::all_zeros(), vout: 0 },
+                script_sig: vec![0x51],
+                sequence: 0xffffffff,
+            }],
+            outputs: vec![
+                TxOut { value: 1000, script_pubkey: p2pk },
+                TxOut { value: 2000, script_pubkey: p2pkh },
+            ],
+            witnesses: None,
+            lock_time: 0,
+        }
+    }
+
+    #[test]
+    fn is_relevant_and_update_flags() {
+        let pubkey = hex_to_vec(PUBKEY).unwrap();
+        let tx = funding_tx(&pubkey);
+        let txid = calculate_txid(&tx);
+        let p2pk_outpoint = OutPoint { txid, vout: 0 };
+        let p2pkh_outpoint = OutPoint { txid, vout: 1 };
+
+        let cases = [
+            (BloomUpdate::None, false, false),
+            (BloomUpdate::All, true, true),
+            (BloomUpdate::P2PubkeyOnly, true, false),
+        ];
+        for (flags, p2pk_added, p2pkh_added) in cases {
+            let mut filter = BloomFilter::new(10, 0.000001, 0, flags);
+            filter.insert_pubkey(&pubkey);
+            assert!(filter.is_relevant_and_update(&tx));
+            assert_eq!(filter.contains_outpoint(&p2pk_outpoint), p2pk_added);
+            assert_eq!(filter.contains_outpoint(&p2pkh_outpoint), p2pkh_added);
+
+            let mut spend = funding_tx(&[0x03; 33]);
+            spend.inputs[0].previous_output = p2pk_outpoint.clone();
+            assert_eq!(filter.is_relevant_and_update(&spend), p2pk_added);
+        }
+    }
+
+    #[test]
+    fn matches_txid_and_addresses() {
+        let tx = funding_tx(&hex_to_vec(PUBKEY).unwrap());
+
+        let mut filter = BloomFilter::new(10, 0.000001, 0, BloomUpdate::None);
+        assert!(!filter.is_relevant_and_update(&tx));
+        filter.insert(calculate_txid(&tx).as_byte_array());
+        assert!(filter.is_relevant_and_update(&tx));
+
+        let mut filter = BloomFilter::new(10, 0.000001, 0, BloomUpdate::None);
+        filter.insert_address("1BgGZ9tcN4rm9KBzDn7KprQz87SZ26SAMH").unwrap();
+        assert!(filter.is_relevant_and_update(&tx));
+        assert!(filter.insert_address("not an address").is_err());
+    }
+
+    #[test]
+    fn serialize_round_trip() {
+        let mut filter = BloomFilter::new(20, 0.001, 0xdeadbeef, BloomUpdate::P2PubkeyOnly);
+        filter.insert(b"element");
+
+        let decoded = BloomFilter::deserialize(&filter.serialize()).unwrap();
+        assert_eq!(decoded.serialize(), filter.serialize());
+        assert_eq!((decoded.hash_funcs, decoded.tweak, decoded.flags), (filter.hash_funcs, 0xdeadbeef, BloomUpdate::P2PubkeyOnly));
+        assert!(decoded.contains(b"element"));
+    }
+
+    #[test]
+    fn deserialize_rejects_invalid_filters() {
+        let mut oversized = Vec::new();
+        write_varint(MAX_BLOOM_FILTER_SIZE + 1, &mut oversized);
+        oversized.resize(oversized.len() + MAX_BLOOM_FILTER_SIZE + 1 + 9, 0);
+        assert!(BloomFilter::deserialize(&oversized).is_err());
+
+        let mut raw = BloomFilter::new(3, 0.01, 0, BloomUpdate::All).serialize();
+        let hash_funcs_at = raw.len() - 9;
+        raw[hash_funcs_at..hash_funcs_at + 4].copy_from_slice(&(MAX_HASH_FUNCS + 1).to_le_bytes());
+        assert!(BloomFilter::deserialize(&raw).is_err());
+
+        assert!(BloomFilter::deserialize(&[]).is_err());
+        assert!(BloomFilter::deserialize(&hex_to_vec("03614e9b0500000000000000").unwrap()).is_err());
+        assert!(BloomFilter::deserialize(&hex_to_vec("03614e9b050000000000000003").unwrap()).is_err());
+    }
+}
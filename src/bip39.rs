@@ -1,4 +1,80 @@
-use crate::hash::pbkdf2_hmac_sha512;
+use crate::entropy::EntropySource;
+use crate::hash::{pbkdf2_hmac_sha512, sha256};
+use crate::error::Error;
+
+const ENGLISH: &str = include_str!("wordlist/english.txt");
+
+pub fn wordlist() -> Vec<&'static str> {
+    ENGLISH.lines().collect()
+}
+
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, Error> {
+    if entropy.len() < 16 || entropy.len() > 32 || !entropy.len().is_multiple_of(4) {
+        return Err(Error::Bip39("Invalid entropy length"));
+    }
+
+    let words = wordlist();
+    let checksum = sha256(entropy);
+    let checksum_bits = entropy.len() / 4;
+    let total_bits = entropy.len() * 8 + checksum_bits;
+
+    let bit_at = |i: usize| -> usize {
+        let byte = if i < entropy.len() * 8 { entropy[i / 8] } else { checksum[(i - entropy.len() * 8) / 8] };
+        ((byte >> (7 - i % 8)) & 1) as usize
+    };
+
+    let mut mnemonic = Vec::with_capacity(total_bits / 11);
+    for word in 0..total_bits / 11 {
+        let mut index = 0;
+        for bit in 0..11 {
+            index = (index << 1) | bit_at(word * 11 + bit);
+        }
+        mnemonic.push(words[index]);
+    }
+
+    Ok(mnemonic.join(" "))
+}
+
+pub fn mnemonic_to_entropy(mnemonic: &str) -> Result<Vec<u8>, Error> {
+    let words = wordlist();
+    let indices = mnemonic
+        .split_whitespace()
+        .map(|word| words.binary_search(&word).map_err(|_| Error::Bip39("Unknown mnemonic word")))
+        .collect::<Result<Vec<usize>, Error>>()?;
+    if !matches!(indices.len(), 12 | 15 | 18 | 21 | 24) {
+        return Err(Error::Bip39("Invalid mnemonic word count"));
+    }
+
+    let total_bits = indices.len() * 11;
+    let checksum_bits = total_bits / 33;
+    let mut data = vec![0u8; total_bits.div_ceil(8)];
+    for (i, index) in indices.iter().enumerate() {
+        for bit in 0..11 {
+            if (index >> (10 - bit)) & 1 == 1 {
+                let position = i * 11 + bit;
+                data[position / 8] |= 0x80 >> (position % 8);
+            }
+        }
+    }
+
+    let entropy_len = (total_bits - checksum_bits) / 8;
+    let entropy = data[..entropy_len].to_vec();
+    if sha256(&entropy)[0] >> (8 - checksum_bits) != data[entropy_len] >> (8 - checksum_bits) {
+        return Err(Error::Bip39("Invalid mnemonic checksum"));
+    }
+
+    Ok(entropy)
+}
+
+pub fn generate_mnemonic<E: EntropySource + ?Sized>(source: &mut E, word_count: usize) -> Result<String, Error> {
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return Err(Error::Bip39("Invalid mnemonic word count"));
+    }
+
+    let mut entropy = vec![0u8; word_count * 4 / 3];
+    source.fill_bytes(&mut entropy)?;
+    entropy_to_mnemonic(&entropy)
+}
 
 pub fn generate_seed(mnemonic: &str, passphrase: &str) -> Vec<u8> {
     let salt = format!("mnemonic{}", passphrase);
@@ -8,4 +84,74 @@ pub fn generate_seed(mnemonic: &str, passphrase: &str) -> Vec<u8> {
         2048,
         64,
     )
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entropy::DeterministicEntropy;
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    // BIP39 reference vectors (passphrase "TREZOR").
+    #[test]
+    fn bip39_vectors() {
+        let vectors = [
+            (
+                "00000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            ),
+            (
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+                "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+            ),
+            (
+                "ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+                "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo vote",
+                "dd48c104698c30cfe2b6142103248622fb7bb0ff692eebb00089b32d22484e1613912f0a5b694407be899ffd31ed3992c456cdf60f5d4564b8ba3f05a69890ad",
+            ),
+        ];
+        for (entropy, mnemonic, seed) in vectors {
+            let entropy = hex_to_vec(entropy).unwrap();
+            assert_eq!(entropy_to_mnemonic(&entropy).unwrap(), mnemonic);
+            assert_eq!(mnemonic_to_entropy(mnemonic).unwrap(), entropy);
+            assert_eq!(vec_to_hex(&generate_seed(mnemonic, "TREZOR")), seed);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_entropy_length() {
+        for len in [0, 12, 15, 17, 21, 36] {
+            assert!(matches!(entropy_to_mnemonic(&vec![0u8; len]), Err(Error::Bip39(_))));
+        }
+        for len in [16, 20, 24, 28, 32] {
+            assert_eq!(entropy_to_mnemonic(&vec![0u8; len]).unwrap().split(' ').count(), len * 3 / 4);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_mnemonics() {
+        let bad_checksum = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon";
+        assert!(matches!(mnemonic_to_entropy(bad_checksum), Err(Error::Bip39("Invalid mnemonic checksum"))));
+        let unknown_word = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abou";
+        assert!(matches!(mnemonic_to_entropy(unknown_word), Err(Error::Bip39("Unknown mnemonic word"))));
+        let short = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert!(matches!(mnemonic_to_entropy(short), Err(Error::Bip39("Invalid mnemonic word count"))));
+    }
+
+    #[test]
+    fn generate_mnemonic_word_counts() {
+        let mut source = DeterministicEntropy::from_u64(0);
+        for word_count in [12, 15, 18, 21, 24] {
+            let mnemonic = generate_mnemonic(&mut source, word_count).unwrap();
+            assert_eq!(mnemonic.split(' ').count(), word_count);
+            assert_eq!(mnemonic_to_entropy(&mnemonic).unwrap().len(), word_count * 4 / 3);
+        }
+        assert!(generate_mnemonic(&mut source, 13).is_err());
+
+        let a = generate_mnemonic(&mut DeterministicEntropy::from_u64(5), 24).unwrap();
+        let b = generate_mnemonic(&mut DeterministicEntropy::from_u64(5), 24).unwrap();
+        assert_eq!(a, b);
+    }
+}
@@ -0,0 +1,409 @@
+use std::fmt;
+
+use secp256k1::{Secp256k1, SecretKey, PublicKey};
+use crate::bip32::master_prv_from_seed;
+use crate::entropy::EntropySource;
+use crate::hash::{hmac_sha512, hash160};
+use crate::utils::bech32::{convert_bits, CHARSET, CHARSET_REV};
+use crate::error::Error;
+
+const MS32_CONST: u128 = 0x10ce0795c2fd1e62a;
+const MS32_LONG_CONST: u128 = 0x43381e570bf4798ab26;
+const MS32_GEN: [u128; 5] = [
+    0x19dc500ce73fde210,
+    0x1bfae00def77fe529,
+    0x1fbd920fffe7bee52,
+    0x1739640bdeee3fdad,
+    0x07729a039cfc75f5a,
+];
+const MS32_LONG_GEN: [u128; 5] = [
+    0x3d59d273535ea62d897,
+    0x7a9becb6361c6c51507,
+    0x543f9b7e6c38d8a2a0e,
+    0x0c577eaeccf1990d13c,
+    0x1887f74f8dc71b10651,
+];
+
+const HRP: &str = "ms";
+const SHORT_CHECKSUM_LEN: usize = 13;
+const LONG_CHECKSUM_LEN: usize = 15;
+const HEADER_LEN: usize = 6;
+const SECRET_INDEX: char = 's';
+const SHARE_INDICES: &str = "acdefghjklmnpqrtuvwxyz023456789";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Codex32Share {
+    data: Vec<u8>,
+}
+
+fn ms32_polymod(values: &[u8], long: bool) -> u128 {
+    let (gen, shift, mask) = if long {
+        (&MS32_LONG_GEN, 70, 0x3fffffffffffffffff)
+    } else {
+        (&MS32_GEN, 60, 0x0fffffffffffffff)
+    };
+
+    let mut residue: u128 = 0x23181b3;
+    for &v in values {
+        let top = residue >> shift;
+        residue = ((residue & mask) << 5) ^ (v as u128);
+        for (i, &g) in gen.iter().enumerate() {
+            if (top >> i) & 1 != 0 {
+                residue ^= g;
+            }
+        }
+    }
+    residue
+}
+
+fn ms32_create_checksum(data: &[u8]) -> Vec<u8> {
+    let long = data.len() > 80;
+    let (len, constant) = if long {
+        (LONG_CHECKSUM_LEN, MS32_LONG_CONST)
+    } else {
+        (SHORT_CHECKSUM_LEN, MS32_CONST)
+    };
+
+    let mut values = data.to_vec();
+    values.extend(std::iter::repeat_n(0, len));
+    let polymod = ms32_polymod(&values, long) ^ constant;
+    (0..len).map(|i| ((polymod >> (5 * (len - 1 - i))) & 31) as u8).collect()
+}
+
+fn ms32_verify_checksum(data: &[u8]) -> bool {
+    if data.len() >= 96 {
+        ms32_polymod(data, true) == MS32_LONG_CONST
+    } else if data.len() <= 93 {
+        ms32_polymod(data, false) == MS32_CONST
+    } else {
+        false
+    }
+}
+
+fn gf32_mul(mut a: u8, b: u8) -> u8 {
+    let mut result = 0;
+    for i in 0..5 {
+        if (b >> i) & 1 != 0 {
+            result ^= a;
+        }
+        a <<= 1;
+        if a & 32 != 0 {
+            a ^= 41;
+        }
+    }
+    result
+}
+
+fn gf32_inv(a: u8) -> u8 {
+    (0..29).fold(a, |acc, _| gf32_mul(acc, a))
+}
+
+fn char_value(c: char) -> Result<u8, Error> {
+    if !c.is_ascii() || CHARSET_REV[c as usize] == -1 {
+        return Err(Error::Codex32("Invalid character in codex32 string"));
+    }
+    Ok(CHARSET_REV[c.to_ascii_lowercase() as usize] as u8)
+}
+
+fn value_char(v: u8) -> char {
+    CHARSET.as_bytes()[v as usize] as char
+}
+
+impl Codex32Share {
+    pub fn threshold(&self) -> usize {
+        value_char(self.data[0]).to_digit(10).unwrap_or(0) as usize
+    }
+
+    pub fn identifier(&self) -> String {
+        self.data[1..5].iter().map(|&v| value_char(v)).collect()
+    }
+
+    pub fn share_index(&self) -> char {
+        value_char(self.data[5])
+    }
+
+    fn checksum_len(&self) -> usize {
+        if self.data.len() >= 96 {
+            LONG_CHECKSUM_LEN
+        } else {
+            SHORT_CHECKSUM_LEN
+        }
+    }
+
+    pub fn payload(&self) -> Vec<u8> {
+        let values = &self.data[HEADER_LEN..self.data.len() - self.checksum_len()];
+        let mut acc: u32 = 0;
+        let mut bits = 0;
+        let mut payload = Vec::with_capacity(values.len() * 5 / 8);
+        for &v in values {
+            acc = (acc << 5) | v as u32;
+            bits += 5;
+            if bits >= 8 {
+                bits -= 8;
+                payload.push((acc >> bits) as u8);
+            }
+        }
+        payload
+    }
+}
+
+impl fmt::Display for Codex32Share {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}1", HRP)?;
+        for &v in &self.data {
+            write!(f, "{}", value_char(v))?;
+        }
+        Ok(())
+    }
+}
+
+fn check_header(threshold: usize, identifier: &str, share_index: char) -> Result<(), Error> {
+    if threshold == 1 || threshold > 9 {
+        return Err(Error::Codex32("Invalid codex32 threshold"));
+    }
+    if identifier.chars().count() != 4 || identifier.chars().any(|c| char_value(c).is_err()) {
+        return Err(Error::Codex32("Invalid codex32 identifier"));
+    }
+    char_value(share_index)?;
+    if threshold == 0 && share_index.to_ascii_lowercase() != SECRET_INDEX {
+        return Err(Error::Codex32("Unshared codex32 secret must use share index 's'"));
+    }
+    Ok(())
+}
+
+fn check_length(len: usize) -> Result<(), Error> {
+    let total = HRP.len() + 1 + len;
+    if !(48..=93).contains(&total) && !(125..=127).contains(&total) {
+        return Err(Error::Codex32("Invalid codex32 string length"));
+    }
+    Ok(())
+}
+
+pub fn codex32_encode(threshold: usize, identifier: &str, share_index: char, payload: &[u8]) -> Result<Codex32Share, Error> {
+    check_header(threshold, identifier, share_index)?;
+
+    let mut data = Vec::with_capacity(HEADER_LEN + payload.len() * 8 / 5 + 1 + LONG_CHECKSUM_LEN);
+    let threshold_char = char::from_digit(threshold as u32, 10).ok_or(Error::Codex32("Invalid codex32 threshold"))?;
+    data.push(char_value(threshold_char)?);
+    for c in identifier.chars() {
+        data.push(char_value(c)?);
+    }
+    data.push(char_value(share_index)?);
+    data.extend(convert_bits(payload, 8, 5, true)?);
+
+    let checksum = ms32_create_checksum(&data);
+    data.extend(checksum);
+    check_length(data.len())?;
+
+    Ok(Codex32Share { data })
+}
+
+pub fn codex32_decode(s: &str) -> Result<Codex32Share, Error> {
+    if s.bytes().any(|b| b.is_ascii_lowercase()) && s.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(Error::Codex32("Invalid codex32 string: mixed case"));
+    }
+    let s = s.to_ascii_lowercase();
+
+    let (hrp, data) = match s.rfind('1') {
+        Some(pos) => (&s[..pos], &s[pos + 1..]),
+        None => return Err(Error::Codex32("Invalid codex32 format: missing separator '1'")),
+    };
+    if hrp != HRP {
+        return Err(Error::Codex32("Invalid codex32 HRP"));
+    }
+    check_length(data.len())?;
+
+    let data = data.chars().map(char_value).collect::<Result<Vec<u8>, _>>()?;
+    if !ms32_verify_checksum(&data) {
+        return Err(Error::Codex32("Invalid codex32 checksum"));
+    }
+
+    let threshold = value_char(data[0]).to_digit(10).ok_or(Error::Codex32("Invalid codex32 threshold"))? as usize;
+    let identifier: String = data[1..5].iter().map(|&v| value_char(v)).collect();
+    check_header(threshold, &identifier, value_char(data[5]))?;
+
+    let share = Codex32Share { data };
+    let payload_len = share.data.len() - HEADER_LEN - share.checksum_len();
+    if payload_len * 5 % 8 > 4 {
+        return Err(Error::Codex32("Invalid codex32 padding"));
+    }
+
+    Ok(share)
+}
+
+pub fn codex32_interpolate(shares: &[Codex32Share], share_index: char) -> Result<Codex32Share, Error> {
+    let first = shares.first().ok_or(Error::Codex32("No codex32 shares provided"))?;
+    let threshold = first.threshold();
+    if threshold == 0 {
+        return Err(Error::Codex32("Unshared codex32 secret cannot be interpolated"));
+    }
+    if shares.len() < threshold {
+        return Err(Error::Codex32("Not enough codex32 shares to reach the threshold"));
+    }
+
+    let shares = &shares[..threshold];
+    for share in shares {
+        if share.data.len() != first.data.len() || share.data[..5] != first.data[..5] {
+            return Err(Error::Codex32("Inconsistent codex32 shares"));
+        }
+    }
+    for (i, share) in shares.iter().enumerate() {
+        if shares[..i].iter().any(|other| other.data[5] == share.data[5]) {
+            return Err(Error::Codex32("Duplicate codex32 share index"));
+        }
+    }
+
+    let x = char_value(share_index)?;
+    if let Some(share) = shares.iter().find(|share| share.data[5] == x) {
+        return Ok(share.clone());
+    }
+
+    let mut data = vec![0u8; first.data.len()];
+    for (i, share) in shares.iter().enumerate() {
+        let xi = share.data[5];
+        let mut weight = 1;
+        for other in shares.iter().filter(|other| other.data[5] != xi) {
+            let xj = other.data[5];
+            weight = gf32_mul(weight, gf32_mul(x ^ xj, gf32_inv(xi ^ xj)));
+        }
+        for (d, &v) in data.iter_mut().zip(&shares[i].data) {
+            *d ^= gf32_mul(weight, v);
+        }
+    }
+
+    Ok(Codex32Share { data })
+}
+
+pub fn codex32_identifier(master_seed: &[u8]) -> Result<String, Error> {
+    let hmac_result = hmac_sha512(b"Bitcoin seed", master_seed);
+    let secp = Secp256k1::new();
+    let sk = SecretKey::from_slice(&hmac_result[..32]).map_err(|_| Error::Codex32("Invalid master private key"))?;
+    let pk = PublicKey::from_secret_key(&secp, &sk);
+
+    let fingerprint = hash160(&pk.serialize());
+    Ok(convert_bits(&fingerprint[..4], 8, 5, true)?[..4].iter().map(|&v| value_char(v)).collect())
+}
+
+pub fn codex32_split<E: EntropySource + ?Sized>(
+    source: &mut E,
+    master_seed: &[u8],
+    identifier: &str,
+    threshold: usize,
+    share_count: usize,
+) -> Result<Vec<Codex32Share>, Error> {
+    if !(2..=9).contains(&threshold) {
+        return Err(Error::Codex32("Invalid codex32 threshold"));
+    }
+    if share_count < threshold || share_count > SHARE_INDICES.len() {
+        return Err(Error::Codex32("Invalid codex32 share count"));
+    }
+
+    let mut base = Vec::with_capacity(threshold);
+    for index in SHARE_INDICES.chars().take(threshold - 1) {
+        let mut payload = vec![0u8; master_seed.len()];
+        source.fill_bytes(&mut payload)?;
+        base.push(codex32_encode(threshold, identifier, index, &payload)?);
+    }
+    base.push(codex32_encode(threshold, identifier, SECRET_INDEX, master_seed)?);
+
+    SHARE_INDICES
+        .chars()
+        .take(share_count)
+        .map(|index| codex32_interpolate(&base, index))
+        .collect()
+}
+
+pub fn codex32_recover(shares: &[&str]) -> Result<Vec<u8>, Error> {
+    let shares = shares.iter().map(|s| codex32_decode(s)).collect::<Result<Vec<_>, _>>()?;
+    let first = shares.first().ok_or(Error::Codex32("No codex32 shares provided"))?;
+
+    if first.threshold() == 0 {
+        return Ok(first.payload());
+    }
+    Ok(codex32_interpolate(&shares, SECRET_INDEX)?.payload())
+}
+
+pub fn codex32_master_prv(shares: &[&str], version: &[u8; 4]) -> Result<Vec<u8>, Error> {
+    let master_seed = codex32_recover(shares)?;
+    master_prv_from_seed(&master_seed, version)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entropy::DeterministicEntropy;
+    use crate::utils::vec_to_hex;
+
+    const SHARE_A: &str = "MS12NAMEA320ZYXWVUTSRQPNMLKJHGFEDCAXRPP870HKKQRM";
+    const SHARE_C: &str = "MS12NAMECACDEFGHJKLMNPQRSTUVWXYZ023FTR2GDZMPY6PN";
+
+    // BIP93 test vector 1.
+    #[test]
+    fn bip93_vector_1() {
+        let share = codex32_decode("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlw").unwrap();
+        assert_eq!(share.threshold(), 0);
+        assert_eq!(share.identifier(), "test");
+        assert_eq!(share.share_index(), 's');
+        assert_eq!(vec_to_hex(&share.payload()), "318c6318c6318c6318c6318c6318c631");
+    }
+
+    // BIP93 test vector 2.
+    #[test]
+    fn bip93_vector_2() {
+        let shares = [codex32_decode(SHARE_A).unwrap(), codex32_decode(SHARE_C).unwrap()];
+        assert_eq!(codex32_interpolate(&shares, 'd').unwrap().to_string(), "ms12namedll4f8jlh4e5vdvuldlfxu2jhdnlsm97xvenrxeg");
+        assert_eq!(codex32_interpolate(&shares, 's').unwrap().to_string(), "ms12names6xqguzttxkeqnjsjzv4jv3nz5k3kwgsphuh6evw");
+        assert_eq!(vec_to_hex(&codex32_recover(&[SHARE_A, SHARE_C]).unwrap()), "d1808e096b35b209ca12132b264662a5");
+    }
+
+    // BIP93 test vector 5.
+    #[test]
+    fn bip93_vector_5() {
+        let share = codex32_decode("MS100C8VSM32ZXFGUHPCHTLUPZRY9X8GF2TVDW0S3JN54KHCE6MUA7LQPZYGSFJD6AN074RXVCEMLH8WU3TK925ACDEFGHJKLMNPQRSTUVWXY06FHPV80UNDVARHRAK").unwrap();
+        assert_eq!(
+            vec_to_hex(&share.payload()),
+            "dc5423251cb87175ff8110c8531d0952d8d73e1194e95b5f19d6f9df7c01111104c9baecdfea8cccc677fb9ddc8aec5553b86e528bcadfdcc201c17c638c47e9"
+        );
+    }
+
+    #[test]
+    fn decode_rejects_bad_strings() {
+        assert!(codex32_decode("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvca9cmczlx").is_err());
+        assert!(codex32_decode("ms10testsxxxxxxxxxxxxxxxxxxxxxxxxxx4nzvcA9cmczlw").is_err());
+        assert!(codex32_recover(&[]).is_err());
+    }
+
+    #[test]
+    fn split_and_recover() {
+        let seed: Vec<u8> = (0..32).collect();
+        let identifier = codex32_identifier(&seed).unwrap();
+        let mut rng = DeterministicEntropy::from_u64(1);
+        let shares: Vec<String> = codex32_split(&mut rng, &seed, &identifier, 3, 5)
+            .unwrap()
+            .iter()
+            .map(|share| share.to_string())
+            .collect();
+
+        for (i, j, k) in [(0, 1, 2), (4, 2, 0), (3, 4, 1)] {
+            assert_eq!(codex32_recover(&[&shares[i], &shares[j], &shares[k]]).unwrap(), seed);
+        }
+        assert!(codex32_recover(&[&shares[0], &shares[1]]).is_err());
+
+        let version = [0x04, 0x88, 0xAD, 0xE4];
+        assert_eq!(
+            codex32_master_prv(&[&shares[0], &shares[1], &shares[2]], &version).unwrap(),
+            master_prv_from_seed(&seed, &version).unwrap()
+        );
+    }
+
+    #[test]
+    fn split_long_seed() {
+        let seed: Vec<u8> = (0..64).collect();
+        let mut rng = DeterministicEntropy::from_u64(2);
+        let shares = codex32_split(&mut rng, &seed, "test", 2, 3).unwrap();
+        assert_eq!(shares[0].to_string().len(), 127);
+        assert_eq!(codex32_recover(&[&shares[2].to_string(), &shares[1].to_string()]).unwrap(), seed);
+        assert!(codex32_split(&mut rng, &seed, "test", 1, 3).is_err());
+        assert!(codex32_split(&mut rng, &seed, "test", 3, 2).is_err());
+    }
+}
@@ -0,0 +1,395 @@
+use secp256k1::{Secp256k1, Message, PublicKey, ecdsa::{RecoverableSignature, RecoveryId, Signature}};
+use crate::address::encode_segwit_address;
+use crate::hash::sha256;
+use crate::utils::base58check_encode;
+use crate::utils::bech32::{convert_bits, decode_bech32_with_limit, Bech32Variant};
+use crate::error::Error;
+
+const MAX_INVOICE_LENGTH: usize = 7089;
+const TIMESTAMP_LEN: usize = 7;
+const SIGNATURE_LEN: usize = 104;
+const DEFAULT_EXPIRY: u64 = 3600;
+const DEFAULT_MIN_FINAL_CLTV_EXPIRY: u64 = 18;
+const MSAT_PER_BTC: u64 = 100_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RouteHint {
+    pub pubkey: PublicKey,
+    pub short_channel_id: u64,
+    pub fee_base_msat: u32,
+    pub fee_proportional_millionths: u32,
+    pub cltv_expiry_delta: u16,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Invoice {
+    pub currency: String,
+    pub amount_msat: Option<u64>,
+    pub timestamp: u64,
+    pub payment_hash: [u8; 32],
+    pub payment_secret: Option<[u8; 32]>,
+    pub description: Option<String>,
+    pub description_hash: Option<[u8; 32]>,
+    pub expiry: u64,
+    pub min_final_cltv_expiry: u64,
+    pub route_hints: Vec<Vec<RouteHint>>,
+    pub fallback_addresses: Vec<String>,
+    pub features: Vec<u8>,
+    pub payee: PublicKey,
+}
+
+impl Invoice {
+    pub fn expires_at(&self) -> u64 {
+        self.timestamp.saturating_add(self.expiry)
+    }
+
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at()
+    }
+}
+
+fn parse_amount(amount: &str) -> Result<Option<u64>, Error> {
+    if amount.is_empty() {
+        return Ok(None);
+    }
+
+    let (digits, multiplier) = match amount.chars().last() {
+        Some(c) if c.is_ascii_digit() => (amount, None),
+        Some(c) => (&amount[..amount.len() - 1], Some(c)),
+        None => return Ok(None),
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(Error::Bolt11("Invalid invoice amount"));
+    }
+    let value: u64 = digits.parse().map_err(|_| Error::Bolt11("Invalid invoice amount"))?;
+
+    let msat = match multiplier {
+        None => value.checked_mul(MSAT_PER_BTC),
+        Some('m') => value.checked_mul(MSAT_PER_BTC / 1_000),
+        Some('u') => value.checked_mul(MSAT_PER_BTC / 1_000_000),
+        Some('n') => value.checked_mul(MSAT_PER_BTC / 1_000_000_000),
+        Some('p') => {
+            if !value.is_multiple_of(10) {
+                return Err(Error::Bolt11("Invoice amount is not a whole number of millisatoshis"));
+            }
+            Some(value / 10)
+        }
+        Some(_) => return Err(Error::Bolt11("Invalid invoice amount multiplier")),
+    };
+
+    msat.ok_or(Error::Bolt11("Invoice amount overflow")).map(Some)
+}
+
+fn parse_hrp(hrp: &str) -> Result<(String, Option<u64>), Error> {
+    let rest = hrp.strip_prefix("ln").ok_or(Error::Bolt11("Invalid invoice prefix"))?;
+    let split = rest.find(|c: char| c.is_ascii_digit()).unwrap_or(rest.len());
+    let (currency, amount) = rest.split_at(split);
+    if currency.is_empty() {
+        return Err(Error::Bolt11("Missing invoice currency"));
+    }
+
+    Ok((currency.to_string(), parse_amount(amount)?))
+}
+
+fn read_int(values: &[u8]) -> Result<u64, Error> {
+    if values.len() > 12 {
+        return Err(Error::Bolt11("Invoice integer field too long"));
+    }
+    Ok(values.iter().fold(0u64, |acc, &v| (acc << 5) | v as u64))
+}
+
+fn read_be(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0u64, |acc, &b| (acc << 8) | b as u64)
+}
+
+fn read_hash(values: &[u8]) -> Option<[u8; 32]> {
+    if values.len() != 52 {
+        return None;
+    }
+    convert_bits(values, 5, 8, false).ok()?.try_into().ok()
+}
+
+fn parse_route_hint(bytes: &[u8]) -> Result<Vec<RouteHint>, Error> {
+    if bytes.is_empty() || !bytes.len().is_multiple_of(51) {
+        return Err(Error::Bolt11("Invalid invoice route hint length"));
+    }
+
+    bytes
+        .chunks_exact(51)
+        .map(|hop| {
+            Ok(RouteHint {
+                pubkey: PublicKey::from_slice(&hop[..33]).map_err(|_| Error::Bolt11("Invalid route hint public key"))?,
+                short_channel_id: read_be(&hop[33..41]),
+                fee_base_msat: read_be(&hop[41..45]) as u32,
+                fee_proportional_millionths: read_be(&hop[45..49]) as u32,
+                cltv_expiry_delta: read_be(&hop[49..51]) as u16,
+            })
+        })
+        .collect()
+}
+
+fn fallback_address(currency: &str, values: &[u8]) -> Option<String> {
+    let (&version, program) = values.split_first()?;
+    let program = convert_bits(program, 5, 8, false).ok()?;
+    let mainnet = currency == "bc";
+
+    match version {
+        0..=16 => {
+            let hrp = match currency {
+                "bc" => "bc",
+                "bcrt" => "bcrt",
+                _ => "tb",
+            };
+            encode_segwit_address(hrp, version, &program).ok()
+        }
+        17 | 18 if program.len() == 20 => {
+            let prefix = match (version, mainnet) {
+                (17, true) => 0x00,
+                (17, false) => 0x6f,
+                (_, true) => 0x05,
+                (_, false) => 0xc4,
+            };
+            let mut payload = vec![prefix];
+            payload.extend_from_slice(&program);
+            Some(base58check_encode(&payload))
+        }
+        _ => None,
+    }
+}
+
+pub fn decode_invoice(invoice: &str) -> Result<Invoice, Error> {
+    let (hrp, data, variant) = decode_bech32_with_limit(invoice, MAX_INVOICE_LENGTH)?;
+    if variant != Bech32Variant::Bech32 {
+        return Err(Error::Bolt11("Invoice must use the Bech32 checksum"));
+    }
+    if data.len() < TIMESTAMP_LEN + SIGNATURE_LEN {
+        return Err(Error::Bolt11("Invoice data part too short"));
+    }
+
+    let (currency, amount_msat) = parse_hrp(&hrp)?;
+    let (fields, signature) = data.split_at(data.len() - SIGNATURE_LEN);
+    let timestamp = read_int(&fields[..TIMESTAMP_LEN])?;
+
+    let mut payment_hash = None;
+    let mut payment_secret = None;
+    let mut description = None;
+    let mut description_hash = None;
+    let mut payee = None;
+    let mut expiry = DEFAULT_EXPIRY;
+    let mut min_final_cltv_expiry = DEFAULT_MIN_FINAL_CLTV_EXPIRY;
+    let mut route_hints = Vec::new();
+    let mut fallback_addresses = Vec::new();
+    let mut features = Vec::new();
+
+    let mut index = TIMESTAMP_LEN;
+    while index < fields.len() {
+        if index + 3 > fields.len() {
+            return Err(Error::Bolt11("Truncated invoice tagged field"));
+        }
+        let tag = fields[index];
+        let len = read_int(&fields[index + 1..index + 3])? as usize;
+        index += 3;
+        if index + len > fields.len() {
+            return Err(Error::Bolt11("Truncated invoice tagged field"));
+        }
+        let values = &fields[index..index + len];
+        index += len;
+
+        match tag {
+            1 if payment_hash.is_none() => payment_hash = read_hash(values),
+            16 if payment_secret.is_none() => payment_secret = read_hash(values),
+            23 if description_hash.is_none() => description_hash = read_hash(values),
+            13 if description.is_none() => {
+                let bytes = convert_bits(values, 5, 8, false)?;
+                description = Some(String::from_utf8(bytes).map_err(|_| Error::Bolt11("Invalid invoice description"))?);
+            }
+            19 if payee.is_none() && len == 53 => {
+                let bytes = convert_bits(values, 5, 8, false)?;
+                payee = Some(PublicKey::from_slice(&bytes).map_err(|_| Error::Bolt11("Invalid invoice payee public key"))?);
+            }
+            6 => expiry = read_int(values)?,
+            24 => min_final_cltv_expiry = read_int(values)?,
+            3 => route_hints.push(parse_route_hint(&convert_bits(values, 5, 8, false)?)?),
+            9 => fallback_addresses.extend(fallback_address(&currency, values)),
+            5 => features = values.to_vec(),
+            _ => {}
+        }
+    }
+
+    let payment_hash = payment_hash.ok_or(Error::Bolt11("Missing invoice payment hash"))?;
+    if description.is_none() && description_hash.is_none() {
+        return Err(Error::Bolt11("Missing invoice description"));
+    }
+
+    let mut preimage = hrp.as_bytes().to_vec();
+    preimage.extend(convert_bits(fields, 5, 8, true)?);
+    let msg = Message::from_digest(sha256(&preimage));
+
+    let signature = convert_bits(signature, 5, 8, false)?;
+    let secp = Secp256k1::new();
+    let payee = match payee {
+        Some(payee) => {
+            let sig = Signature::from_compact(&signature[..64]).map_err(|_| Error::Bolt11("Invalid invoice signature"))?;
+            secp.verify_ecdsa(&msg, &sig, &payee).map_err(|_| Error::Bolt11("Invalid invoice signature"))?;
+            payee
+        }
+        None => {
+            let rec_id = RecoveryId::try_from(signature[64] as i32).map_err(|_| Error::Bolt11("Invalid invoice recovery id"))?;
+            let rec_sig = RecoverableSignature::from_compact(&signature[..64], rec_id)
+                .map_err(|_| Error::Bolt11("Invalid invoice signature"))?;
+            secp.recover_ecdsa(&msg, &rec_sig).map_err(|_| Error::Bolt11("Unable to recover invoice payee"))?
+        }
+    };
+
+    Ok(Invoice {
+        currency,
+        amount_msat,
+        timestamp,
+        payment_hash,
+        payment_secret,
+        description,
+        description_hash,
+        expiry,
+        min_final_cltv_expiry,
+        route_hints,
+        fallback_addresses,
+        features,
+        payee,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::bech32::encode_bech32;
+    use crate::utils::vec_to_hex;
+
+    const PAYEE: &str = "03e7156ae33b0a208d0744199163177e909e80176e55d97a2f221ede0f934dd9ad";
+    const PAYMENT_HASH: &str = "0001020304050607080900010203040506070809000102030405060708090102";
+    const DESCRIPTION_HASH: &str = "3925b6f67e2c340036ed12093dd44e0368df1b6ea26c53dbe4811f58fd5db8c1";
+
+    const DONATION: &str = "lnbc1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdpl2pkx2ctnv5sxxmmwwd5kgetjypeh2ursdae8g6twvus8g6rfwvs8qun0dfjkxaq9qrsgq357wnc5r2ueh7ck6q93dj32dlqnls087fxdwk8qakdyafkq3yap9us6v52vjjsrvywa6rt52cm9r9zqt8r2t7mlcwspyetp5h2tztugp9lfyql";
+    const COFFEE: &str = "lnbc2500u1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqdq5xysxxatsyp3k7enxv4jsxqzpu9qrsgquk0rl77nj30yxdy8j9vdx85fkpmdla2087ne0xh8nhedh8w27kyke0lp53ut353s06fv3qfegext0eh0ymjpf39tuven09sam30g4vgpfna3rh";
+    const HASHED_DESCRIPTION: &str = "lnbc20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqhp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqs9qrsgq7ea976txfraylvgzuxs8kgcw23ezlrszfnh8r6qtfpr6cxga50aj6txm9rxrydzd06dfeawfk6swupvz4erwnyutnjq7x39ymw6j38gp7ynn44";
+    const ROUTE_HINT: &str = "lnbc20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqhp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqsfpp3qjmp7lwpagxun9pygexvgpjdc4jdj85fr9yq20q82gphp2nflc7jtzrcazrra7wwgzxqc8u7754cdlpfrmccae92qgzqvzq2ps8pqqqqqqpqqqqq9qqqvpeuqafqxu92d8lr6fvg0r5gv0heeeqgcrqlnm6jhphu9y00rrhy4grqszsvpcgpy9qqqqqqgqqqqq7qqzq9qrsgqdfjcdk6w3ak5pca9hwfwfh63zrrz06wwfya0ydlzpgzxkn5xagsqz7x9j4jwe7yj7vaf2k9lqsdk45kts2fd0fkr28am0u4w95tt2nsq76cqw0";
+
+    // BOLT11 examples: on-chain fallback addresses of every kind.
+    const FALLBACKS: [(&str, &str); 4] = [
+        (
+            "lntb20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygshp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqfpp3x9et2e20v6pu37c5d9vax37wxq72un989qrsgqdj545axuxtnfemtpwkc45hx9d2ft7x04mt8q7y6t0k2dge9e7h8kpy9p34ytyslj3yu569aalz2xdk8xkd7ltxqld94u8h2esmsmacgpghe9k8",
+            "mk2QpYatsKicvFVuTAQLBryyccRXMUaGHP",
+        ),
+        (
+            "lnbc20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygshp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqfppj3a24vwu6r8ejrss3axul8rxldph2q7z99qrsgqz6qsgww34xlatfj6e3sngrwfy3ytkt29d2qttr8qz2mnedfqysuqypgqex4haa2h8fx3wnypranf3pdwyluftwe680jjcfp438u82xqphf75ym",
+            "3EktnHQD7RiAE6uzMj2ZifT9YgRrkSgzQX",
+        ),
+        (
+            "lnbc20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygshp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqfppqw508d6qejxtdg4y5r3zarvary0c5xw7k9qrsgqt29a0wturnys2hhxpner2e3plp6jyj8qx7548zr2z7ptgjjc7hljm98xhjym0dg52sdrvqamxdezkmqg4gdrvwwnf0kv2jdfnl4xatsqmrnsse",
+            "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4",
+        ),
+        (
+            "lnbc20m1pvjluezsp5zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zyg3zygshp58yjmdan79s6qqdhdzgynm4zwqd5d7xmw5fk98klysy043l2ahrqspp5qqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqqqsyqcyq5rqwzqfqypqfp4qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3q9qrsgq9vlvyj8cqvq6ggvpwd53jncp9nwc47xlrsnenq2zp70fq83qlgesn4u3uyf4tesfkkwwfg3qs54qe426hp3tz7z6sweqdjg05axsrjqp9yrrwc",
+            "bc1qrp33g0q5c5txsp9arysrx4k6zdkfs4nce4xj0gdcccefvpysxf3qccfmv3",
+        ),
+    ];
+
+    #[test]
+    fn bolt11_donation() {
+        let invoice = decode_invoice(DONATION).unwrap();
+        assert_eq!(invoice.currency, "bc");
+        assert_eq!(invoice.amount_msat, None);
+        assert_eq!(invoice.timestamp, 1496314658);
+        assert_eq!(vec_to_hex(&invoice.payment_hash), PAYMENT_HASH);
+        assert_eq!(invoice.payment_secret, Some([0x11; 32]));
+        assert_eq!(invoice.description.as_deref(), Some("Please consider supporting this project"));
+        assert_eq!(invoice.expiry, DEFAULT_EXPIRY);
+        assert_eq!(invoice.payee.to_string(), PAYEE);
+    }
+
+    #[test]
+    fn bolt11_coffee() {
+        let invoice = decode_invoice(COFFEE).unwrap();
+        assert_eq!(invoice.amount_msat, Some(250_000_000));
+        assert_eq!(invoice.description.as_deref(), Some("1 cup coffee"));
+        assert_eq!(invoice.expiry, 60);
+        assert_eq!(invoice.payee.to_string(), PAYEE);
+    }
+
+    #[test]
+    fn bolt11_description_hash() {
+        let invoice = decode_invoice(HASHED_DESCRIPTION).unwrap();
+        assert_eq!(invoice.amount_msat, Some(2_000_000_000));
+        assert_eq!(invoice.description, None);
+        assert_eq!(invoice.description_hash.map(|hash| vec_to_hex(&hash)).as_deref(), Some(DESCRIPTION_HASH));
+        assert_eq!(invoice.payee.to_string(), PAYEE);
+    }
+
+    #[test]
+    fn bolt11_route_hint() {
+        let invoice = decode_invoice(ROUTE_HINT).unwrap();
+        assert_eq!(invoice.fallback_addresses, ["1RustyRX2oai4EYYDpQGWvEL62BBGqN9T"]);
+        assert_eq!(invoice.route_hints.len(), 1);
+
+        let hops = &invoice.route_hints[0];
+        assert_eq!(hops.len(), 2);
+        assert_eq!(hops[0].pubkey.to_string(), "029e03a901b85534ff1e92c43c74431f7ce72046060fcf7a95c37e148f78c77255");
+        assert_eq!(hops[0].short_channel_id, 0x0102030405060708);
+        assert_eq!((hops[0].fee_base_msat, hops[0].fee_proportional_millionths, hops[0].cltv_expiry_delta), (1, 20, 3));
+        assert_eq!(hops[1].pubkey.to_string(), "039e03a901b85534ff1e92c43c74431f7ce72046060fcf7a95c37e148f78c77255");
+        assert_eq!(hops[1].short_channel_id, 0x030405060708090a);
+        assert_eq!((hops[1].fee_base_msat, hops[1].fee_proportional_millionths, hops[1].cltv_expiry_delta), (2, 30, 4));
+    }
+
+    #[test]
+    fn bolt11_fallback_addresses() {
+        for (invoice, address) in FALLBACKS {
+            let invoice = decode_invoice(invoice).unwrap();
+            assert_eq!(invoice.fallback_addresses, [address]);
+            assert_eq!(invoice.payee.to_string(), PAYEE);
+        }
+    }
+
+    #[test]
+    fn bolt11_uppercase() {
+        assert_eq!(decode_invoice(&COFFEE.to_uppercase()).unwrap(), decode_invoice(COFFEE).unwrap());
+    }
+
+    #[test]
+    fn bolt11_rejects_bech32m() {
+        let (hrp, data, _) = decode_bech32_with_limit(COFFEE, MAX_INVOICE_LENGTH).unwrap();
+        let bech32m = encode_bech32(&hrp, &data, Bech32Variant::Bech32m).unwrap();
+        assert!(decode_invoice(&bech32m).is_err());
+    }
+
+    #[test]
+    fn bolt11_tampered_fields() {
+        let (hrp, mut data, _) = decode_bech32_with_limit(COFFEE, MAX_INVOICE_LENGTH).unwrap();
+        data[TIMESTAMP_LEN - 1] ^= 1;
+        let tampered = encode_bech32(&hrp, &data, Bech32Variant::Bech32).unwrap();
+        assert!(decode_invoice(&tampered).is_ok_and(|invoice| invoice.payee.to_string() != PAYEE));
+
+        // The recovery id occupies the low bits of the last two groups.
+        let (hrp, mut data, _) = decode_bech32_with_limit(COFFEE, MAX_INVOICE_LENGTH).unwrap();
+        let index = data.len() - 2;
+        data[index] ^= 1;
+        let tampered = encode_bech32(&hrp, &data, Bech32Variant::Bech32).unwrap();
+        assert!(decode_invoice(&tampered).is_err());
+    }
+
+    #[test]
+    fn bolt11_integer_field_length() {
+        assert_eq!(read_int(&[31; 12]).unwrap(), (1 << 60) - 1);
+        assert!(read_int(&[31; 13]).is_err());
+
+        let with_expiry = |values: usize| {
+            let (hrp, mut data, _) = decode_bech32_with_limit(COFFEE, MAX_INVOICE_LENGTH).unwrap();
+            let mut field = vec![6, 0, values as u8];
+            field.extend(std::iter::repeat_n(1, values));
+            let end = data.len() - SIGNATURE_LEN;
+            data.splice(end..end, field);
+            decode_invoice(&encode_bech32(&hrp, &data, Bech32Variant::Bech32).unwrap())
+        };
+        assert_eq!(with_expiry(12).unwrap().expiry, (0..12).fold(0, |acc, _| (acc << 5) | 1));
+        assert!(matches!(with_expiry(13), Err(Error::Bolt11(_))));
+    }
+}
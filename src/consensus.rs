@@ -0,0 +1,331 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use crate::hash::{Txid, Wtxid, BlockHash, Hash256, Hash160};
+
+pub const MAX_VEC_SIZE: usize = 4_000_000;
+
+#[derive(Debug)]
+pub enum EncodeError {
+    Io(io::Error),
+    NonMinimalVarInt,
+    OversizedVectorAllocation { requested: usize, max: usize },
+    ParseFailed(&'static str),
+}
+
+impl fmt::Display for EncodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodeError::Io(e) => write!(f, "I/O error: {}", e),
+            EncodeError::NonMinimalVarInt => write!(f, "Non-minimal CompactSize encoding"),
+            EncodeError::OversizedVectorAllocation { requested, max } => {
+                write!(f, "Allocation of {} bytes exceeds the maximum of {}", requested, max)
+            }
+            EncodeError::ParseFailed(msg) => write!(f, "Parse failed: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for EncodeError {}
+
+impl From<io::Error> for EncodeError {
+    fn from(e: io::Error) -> Self {
+        EncodeError::Io(e)
+    }
+}
+
+pub trait Encodable {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize>;
+}
+
+pub trait Decodable: Sized {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError>;
+}
+
+pub fn serialize<T: Encodable + ?Sized>(data: &T) -> Vec<u8> {
+    let mut encoded = Vec::new();
+    data.consensus_encode(&mut encoded).expect("Writing to a Vec never fails");
+    encoded
+}
+
+pub fn deserialize_partial<T: Decodable>(data: &[u8]) -> Result<(T, usize), EncodeError> {
+    let mut reader = data;
+    let decoded = T::consensus_decode(&mut reader)?;
+    Ok((decoded, data.len() - reader.len()))
+}
+
+pub fn deserialize<T: Decodable>(data: &[u8]) -> Result<T, EncodeError> {
+    let (decoded, consumed) = deserialize_partial(data)?;
+    if consumed != data.len() {
+        return Err(EncodeError::ParseFailed("Data not consumed entirely"));
+    }
+    Ok(decoded)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VarInt(pub u64);
+
+impl VarInt {
+    pub fn size(&self) -> usize {
+        match self.0 {
+            0..=0xFC => 1,
+            0xFD..=0xFFFF => 3,
+            0x10000..=0xFFFFFFFF => 5,
+            _ => 9,
+        }
+    }
+}
+
+impl Encodable for VarInt {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        match self.size() {
+            1 => writer.write_all(&[self.0 as u8])?,
+            3 => {
+                writer.write_all(&[0xFD])?;
+                writer.write_all(&(self.0 as u16).to_le_bytes())?;
+            }
+            5 => {
+                writer.write_all(&[0xFE])?;
+                writer.write_all(&(self.0 as u32).to_le_bytes())?;
+            }
+            _ => {
+                writer.write_all(&[0xFF])?;
+                writer.write_all(&self.0.to_le_bytes())?;
+            }
+        }
+        Ok(self.size())
+    }
+}
+
+impl Decodable for VarInt {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let value = match u8::consensus_decode(reader)? {
+            0xFD => {
+                let value = u16::consensus_decode(reader)? as u64;
+                if value < 0xFD {
+                    return Err(EncodeError::NonMinimalVarInt);
+                }
+                value
+            }
+            0xFE => {
+                let value = u32::consensus_decode(reader)? as u64;
+                if value <= 0xFFFF {
+                    return Err(EncodeError::NonMinimalVarInt);
+                }
+                value
+            }
+            0xFF => {
+                let value = u64::consensus_decode(reader)?;
+                if value <= 0xFFFFFFFF {
+                    return Err(EncodeError::NonMinimalVarInt);
+                }
+                value
+            }
+            first => first as u64,
+        };
+        Ok(VarInt(value))
+    }
+}
+
+macro_rules! impl_int_encodable {
+    ($($ty:ty),*) => {
+        $(
+            impl Encodable for $ty {
+                fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+                    writer.write_all(&self.to_le_bytes())?;
+                    Ok(std::mem::size_of::<$ty>())
+                }
+            }
+
+            impl Decodable for $ty {
+                fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+                    let mut bytes = [0u8; std::mem::size_of::<$ty>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+impl_int_encodable!(u8, u16, u32, u64, i32, i64);
+
+impl Encodable for bool {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        (*self as u8).consensus_encode(writer)
+    }
+}
+
+impl Decodable for bool {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        Ok(u8::consensus_decode(reader)? != 0)
+    }
+}
+
+impl<const N: usize> Encodable for [u8; N] {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        writer.write_all(self)?;
+        Ok(N)
+    }
+}
+
+impl<const N: usize> Decodable for [u8; N] {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let mut bytes = [0u8; N];
+        reader.read_exact(&mut bytes)?;
+        Ok(bytes)
+    }
+}
+
+impl Encodable for [u8] {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        let len = VarInt(self.len() as u64).consensus_encode(writer)?;
+        writer.write_all(self)?;
+        Ok(len + self.len())
+    }
+}
+
+impl Encodable for Vec<u8> {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        self.as_slice().consensus_encode(writer)
+    }
+}
+
+impl Decodable for Vec<u8> {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let len = decode_length(reader, 1)?;
+
+        let mut bytes = Vec::new();
+        reader.take(len as u64).read_to_end(&mut bytes)?;
+        if bytes.len() != len {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+        Ok(bytes)
+    }
+}
+
+pub(crate) fn decode_length<R: Read + ?Sized>(reader: &mut R, item_size: usize) -> Result<usize, EncodeError> {
+    let len = VarInt::consensus_decode(reader)?.0;
+    let requested = (len as usize).saturating_mul(item_size.max(1));
+    if len > MAX_VEC_SIZE as u64 || requested > MAX_VEC_SIZE {
+        return Err(EncodeError::OversizedVectorAllocation { requested, max: MAX_VEC_SIZE });
+    }
+    Ok(len as usize)
+}
+
+macro_rules! impl_vec_encodable {
+    ($($ty:ty),*) => {
+        $(
+            impl $crate::consensus::Encodable for Vec<$ty> {
+                fn consensus_encode<W: std::io::Write + ?Sized>(&self, writer: &mut W) -> std::io::Result<usize> {
+                    let mut len = $crate::consensus::VarInt(self.len() as u64).consensus_encode(writer)?;
+                    for item in self {
+                        len += item.consensus_encode(writer)?;
+                    }
+                    Ok(len)
+                }
+            }
+
+            impl $crate::consensus::Decodable for Vec<$ty> {
+                fn consensus_decode<R: std::io::Read + ?Sized>(reader: &mut R) -> Result<Self, $crate::consensus::EncodeError> {
+                    let len = $crate::consensus::decode_length(reader, std::mem::size_of::<$ty>())?;
+                    let mut items = Vec::with_capacity(len.min(1024));
+                    for _ in 0..len {
+                        items.push(<$ty>::consensus_decode(reader)?);
+                    }
+                    Ok(items)
+                }
+            }
+        )*
+    };
+}
+
+pub(crate) use impl_vec_encodable;
+
+impl_vec_encodable!(Vec<u8>, u64, [u8; 32]);
+
+macro_rules! impl_hash_encodable {
+    ($($ty:ident),*) => {
+        $(
+            impl Encodable for $ty {
+                fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+                    self.as_byte_array().consensus_encode(writer)
+                }
+            }
+
+            impl Decodable for $ty {
+                fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+                    Ok($ty::from_byte_array(Decodable::consensus_decode(reader)?))
+                }
+            }
+        )*
+    };
+}
+
+impl_hash_encodable!(Txid, Wtxid, BlockHash, Hash256, Hash160);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    #[test]
+    fn varint_round_trip() {
+        let cases = [
+            (0, "00"),
+            (0xfc, "fc"),
+            (0xfd, "fdfd00"),
+            (0xffff, "fdffff"),
+            (0x10000, "fe00000100"),
+            (0xffffffff, "feffffffff"),
+            (0x100000000, "ff0000000001000000"),
+        ];
+        for (value, hex) in cases {
+            let encoded = serialize(&VarInt(value));
+            assert_eq!(vec_to_hex(&encoded), hex);
+            assert_eq!(VarInt(value).size(), encoded.len());
+            assert_eq!(deserialize::<VarInt>(&encoded).unwrap(), VarInt(value));
+        }
+    }
+
+    #[test]
+    fn varint_non_minimal() {
+        for hex in ["fdfc00", "feffff0000", "ffffffffff00000000"] {
+            let encoded = hex_to_vec(hex).unwrap();
+            assert!(matches!(deserialize::<VarInt>(&encoded), Err(EncodeError::NonMinimalVarInt)));
+        }
+    }
+
+    #[test]
+    fn integers_little_endian() {
+        assert_eq!(vec_to_hex(&serialize(&0x01020304u32)), "04030201");
+        assert_eq!(deserialize::<u64>(&hex_to_vec("0100000000000080").unwrap()).unwrap(), 0x8000000000000001);
+        assert_eq!(deserialize::<i32>(&hex_to_vec("ffffffff").unwrap()).unwrap(), -1);
+        assert!(deserialize::<bool>(&[1]).unwrap());
+    }
+
+    #[test]
+    fn vector_round_trip() {
+        let data = vec![0xabu8; 300];
+        let encoded = serialize(&data);
+        assert_eq!(vec_to_hex(&encoded[..3]), "fd2c01");
+        assert_eq!(deserialize::<Vec<u8>>(&encoded).unwrap(), data);
+
+        let hashes = vec![[7u8; 32], [9u8; 32]];
+        assert_eq!(deserialize::<Vec<[u8; 32]>>(&serialize(&hashes)).unwrap(), hashes);
+    }
+
+    #[test]
+    fn oversized_allocation() {
+        let encoded = hex_to_vec("feffffff7f").unwrap();
+        assert!(matches!(deserialize::<Vec<u8>>(&encoded), Err(EncodeError::OversizedVectorAllocation { .. })));
+        let encoded = hex_to_vec("fe00093d00").unwrap();
+        assert!(matches!(deserialize::<Vec<u8>>(&encoded), Err(EncodeError::Io(_))));
+    }
+
+    #[test]
+    fn trailing_data() {
+        assert!(matches!(deserialize::<u16>(&[1, 2, 3]), Err(EncodeError::ParseFailed(_))));
+        assert_eq!(deserialize_partial::<u16>(&[1, 2, 3]).unwrap(), (0x0201, 2));
+    }
+}
@@ -0,0 +1,166 @@
+use std::fs::File;
+use std::io::Read;
+
+use crate::hash::chacha20_block;
+use crate::error::Error;
+
+pub trait EntropySource {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error>;
+
+    fn next_u32(&mut self) -> Result<u32, Error> {
+        let mut bytes = [0u8; 4];
+        self.fill_bytes(&mut bytes)?;
+        Ok(u32::from_le_bytes(bytes))
+    }
+
+    fn next_u64(&mut self) -> Result<u64, Error> {
+        let mut bytes = [0u8; 8];
+        self.fill_bytes(&mut bytes)?;
+        Ok(u64::from_le_bytes(bytes))
+    }
+
+    fn gen_range(&mut self, upper: u64) -> Result<u64, Error> {
+        if upper == 0 {
+            return Err(Error::Entropy("Empty range"));
+        }
+
+        let zone = u64::MAX - (u64::MAX % upper);
+        loop {
+            let value = self.next_u64()?;
+            if value < zone {
+                return Ok(value % upper);
+            }
+        }
+    }
+
+    fn shuffle<T>(&mut self, items: &mut [T]) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        for i in (1..items.len()).rev() {
+            let j = self.gen_range(i as u64 + 1)? as usize;
+            items.swap(i, j);
+        }
+        Ok(())
+    }
+}
+
+pub struct OsEntropy;
+
+impl EntropySource for OsEntropy {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        let mut urandom = File::open("/dev/urandom").map_err(|_| Error::Entropy("Unable to open /dev/urandom"))?;
+        urandom.read_exact(dest).map_err(|_| Error::Entropy("Unable to read from /dev/urandom"))
+    }
+}
+
+#[derive(Clone)]
+pub struct DeterministicEntropy {
+    key: [u8; 32],
+    counter: u32,
+    block: [u8; 64],
+    position: usize,
+}
+
+impl DeterministicEntropy {
+    pub fn from_seed(seed: [u8; 32]) -> Self {
+        DeterministicEntropy {
+            key: seed,
+            counter: 0,
+            block: [0u8; 64],
+            position: 64,
+        }
+    }
+
+    pub fn from_u64(seed: u64) -> Self {
+        let mut key = [0u8; 32];
+        key[..8].copy_from_slice(&seed.to_le_bytes());
+        Self::from_seed(key)
+    }
+}
+
+impl EntropySource for DeterministicEntropy {
+    fn fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        for byte in dest.iter_mut() {
+            if self.position == 64 {
+                self.block = chacha20_block(&self.key, self.counter, &[0u8; 12]);
+                self.counter = self.counter.wrapping_add(1);
+                self.position = 0;
+            }
+            *byte = self.block[self.position];
+            self.position += 1;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // RFC 8439 A.1, test vector #1: all-zero key, nonce and counter.
+    #[test]
+    fn zero_seed_matches_chacha20_keystream() {
+        let mut source = DeterministicEntropy::from_seed([0u8; 32]);
+        let mut first = [0u8; 40];
+        let mut rest = [0u8; 24];
+        source.fill_bytes(&mut first).unwrap();
+        source.fill_bytes(&mut rest).unwrap();
+        assert_eq!(
+            vec_to_hex(&[first.as_slice(), rest.as_slice()].concat()),
+            "76b8e0ada0f13d90405d6ae55386bd28bdd219b8a08ded1aa836efcc8b770dc7\
+             da41597c5157488d7724e03fb8d84a376a43b8f41518a11cc387b669b2ee6586"
+        );
+    }
+
+    #[test]
+    fn from_seed_is_reproducible() {
+        let mut a = DeterministicEntropy::from_u64(42);
+        let mut b = DeterministicEntropy::from_u64(42);
+        let mut c = DeterministicEntropy::from_u64(43);
+        let (mut x, mut y, mut z) = ([0u8; 200], [0u8; 200], [0u8; 200]);
+        a.fill_bytes(&mut x).unwrap();
+        b.fill_bytes(&mut y).unwrap();
+        c.fill_bytes(&mut z).unwrap();
+        assert_eq!(x, y);
+        assert_ne!(x, z);
+        assert_eq!(a.next_u64().unwrap(), b.next_u64().unwrap());
+    }
+
+    #[test]
+    fn gen_range_stays_in_bounds() {
+        let mut source = DeterministicEntropy::from_u64(7);
+        assert!(matches!(source.gen_range(0), Err(Error::Entropy(_))));
+        for upper in [1u64, 2, 3, 10, 1000, u64::MAX] {
+            for _ in 0..100 {
+                assert!(source.gen_range(upper).unwrap() < upper);
+            }
+        }
+    }
+
+    #[test]
+    fn shuffle_is_a_deterministic_permutation() {
+        let original: Vec<u32> = (0..50).collect();
+        let mut a = original.clone();
+        let mut b = original.clone();
+        DeterministicEntropy::from_u64(1).shuffle(&mut a).unwrap();
+        DeterministicEntropy::from_u64(1).shuffle(&mut b).unwrap();
+        assert_eq!(a, b);
+        assert_ne!(a, original);
+
+        let mut sorted = a.clone();
+        sorted.sort();
+        assert_eq!(sorted, original);
+
+        let mut empty: [u32; 0] = [];
+        DeterministicEntropy::from_u64(1).shuffle(&mut empty).unwrap();
+    }
+
+    #[test]
+    fn os_entropy_fills_buffer() {
+        let mut buffer = [0u8; 64];
+        OsEntropy.fill_bytes(&mut buffer).unwrap();
+        assert!(buffer.iter().any(|&b| b != 0));
+    }
+}
@@ -0,0 +1,90 @@
+use std::fmt;
+
+use crate::consensus::EncodeError;
+use crate::utils::{Base58Error, Base64Error, HexError};
+
+#[derive(Debug)]
+pub enum Error {
+    Address(&'static str),
+    Base58(Base58Error),
+    Base64(Base64Error),
+    Bech32(&'static str),
+    Bip32(&'static str),
+    Bip37(&'static str),
+    Bip39(&'static str),
+    Bolt11(&'static str),
+    Codex32(&'static str),
+    Encode(EncodeError),
+    Entropy(&'static str),
+    Hash(&'static str),
+    Hex(HexError),
+    Secp256k1(secp256k1::Error),
+    Signature(&'static str),
+    Undo(&'static str),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Address(msg) => write!(f, "Address error: {}", msg),
+            Error::Base58(e) => write!(f, "Base58 error: {}", e),
+            Error::Base64(e) => write!(f, "Base64 error: {}", e),
+            Error::Bech32(msg) => write!(f, "Bech32 error: {}", msg),
+            Error::Bip32(msg) => write!(f, "BIP32 error: {}", msg),
+            Error::Bip37(msg) => write!(f, "BIP37 error: {}", msg),
+            Error::Bip39(msg) => write!(f, "BIP39 error: {}", msg),
+            Error::Bolt11(msg) => write!(f, "BOLT11 error: {}", msg),
+            Error::Codex32(msg) => write!(f, "Codex32 error: {}", msg),
+            Error::Encode(e) => write!(f, "Consensus encoding error: {}", e),
+            Error::Entropy(msg) => write!(f, "Entropy error: {}", msg),
+            Error::Hash(msg) => write!(f, "Hash error: {}", msg),
+            Error::Hex(e) => write!(f, "Hex error: {}", e),
+            Error::Secp256k1(e) => write!(f, "secp256k1 error: {}", e),
+            Error::Signature(msg) => write!(f, "Signature error: {}", msg),
+            Error::Undo(msg) => write!(f, "Undo data error: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Base58(e) => Some(e),
+            Error::Base64(e) => Some(e),
+            Error::Encode(e) => Some(e),
+            Error::Hex(e) => Some(e),
+            Error::Secp256k1(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<Base58Error> for Error {
+    fn from(e: Base58Error) -> Self {
+        Error::Base58(e)
+    }
+}
+
+impl From<Base64Error> for Error {
+    fn from(e: Base64Error) -> Self {
+        Error::Base64(e)
+    }
+}
+
+impl From<EncodeError> for Error {
+    fn from(e: EncodeError) -> Self {
+        Error::Encode(e)
+    }
+}
+
+impl From<HexError> for Error {
+    fn from(e: HexError) -> Self {
+        Error::Hex(e)
+    }
+}
+
+impl From<secp256k1::Error> for Error {
+    fn from(e: secp256k1::Error) -> Self {
+        Error::Secp256k1(e)
+    }
+}
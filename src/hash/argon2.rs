@@ -0,0 +1,351 @@
+use crate::hash::blake2b::{Blake2b, MAX_OUTPUT_SIZE};
+use crate::error::Error;
+
+const BLOCK_WORDS: usize = 128;
+const BLOCK_BYTES: usize = BLOCK_WORDS * 8;
+const SYNC_POINTS: u32 = 4;
+const VERSION: u32 = 0x13;
+const ARGON2ID: u32 = 2;
+
+type Block = [u64; BLOCK_WORDS];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Argon2Params {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+    pub output_len: usize,
+}
+
+impl Default for Argon2Params {
+    fn default() -> Self {
+        Argon2Params {
+            memory_kib: 64 * 1024,
+            iterations: 3,
+            parallelism: 4,
+            output_len: 32,
+        }
+    }
+}
+
+fn blake2b_long(inputs: &[&[u8]], output: &mut [u8]) -> Result<(), Error> {
+    let output_len = output.len();
+    let len_prefix = (output_len as u32).to_le_bytes();
+
+    if output_len <= MAX_OUTPUT_SIZE {
+        let mut hasher = Blake2b::new(output_len)?;
+        hasher.update(&len_prefix);
+        for input in inputs {
+            hasher.update(input);
+        }
+        return hasher.finalize_into(output);
+    }
+
+    let mut v = [0u8; MAX_OUTPUT_SIZE];
+    let mut hasher = Blake2b::new(MAX_OUTPUT_SIZE)?;
+    hasher.update(&len_prefix);
+    for input in inputs {
+        hasher.update(input);
+    }
+    hasher.finalize_into(&mut v)?;
+
+    let mut position = 0;
+    output[..32].copy_from_slice(&v[..32]);
+    position += 32;
+
+    while output_len - position > MAX_OUTPUT_SIZE {
+        let mut hasher = Blake2b::new(MAX_OUTPUT_SIZE)?;
+        hasher.update(&v);
+        hasher.finalize_into(&mut v)?;
+        output[position..position + 32].copy_from_slice(&v[..32]);
+        position += 32;
+    }
+
+    let mut hasher = Blake2b::new(output_len - position)?;
+    hasher.update(&v);
+    hasher.finalize_into(&mut output[position..])
+}
+
+fn mul_add(a: u64, b: u64) -> u64 {
+    a.wrapping_add(b)
+        .wrapping_add(2u64.wrapping_mul(a & 0xffffffff).wrapping_mul(b & 0xffffffff))
+}
+
+fn gb(v: &mut Block, a: usize, b: usize, c: usize, d: usize) {
+    v[a] = mul_add(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = mul_add(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = mul_add(v[a], v[b]);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = mul_add(v[c], v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+fn permute(v: &mut Block, idx: &[usize; 16]) {
+    gb(v, idx[0], idx[4], idx[8], idx[12]);
+    gb(v, idx[1], idx[5], idx[9], idx[13]);
+    gb(v, idx[2], idx[6], idx[10], idx[14]);
+    gb(v, idx[3], idx[7], idx[11], idx[15]);
+    gb(v, idx[0], idx[5], idx[10], idx[15]);
+    gb(v, idx[1], idx[6], idx[11], idx[12]);
+    gb(v, idx[2], idx[7], idx[8], idx[13]);
+    gb(v, idx[3], idx[4], idx[9], idx[14]);
+}
+
+fn compress(x: &Block, y: &Block) -> Block {
+    let mut r = [0u64; BLOCK_WORDS];
+    for i in 0..BLOCK_WORDS {
+        r[i] = x[i] ^ y[i];
+    }
+
+    let mut q = r;
+    for row in 0..8 {
+        let mut idx = [0usize; 16];
+        for (j, slot) in idx.iter_mut().enumerate() {
+            *slot = row * 16 + j;
+        }
+        permute(&mut q, &idx);
+    }
+    for column in 0..8 {
+        let mut idx = [0usize; 16];
+        for (j, slot) in idx.iter_mut().enumerate() {
+            *slot = (j / 2) * 16 + column * 2 + (j % 2);
+        }
+        permute(&mut q, &idx);
+    }
+
+    for i in 0..BLOCK_WORDS {
+        q[i] ^= r[i];
+    }
+    q
+}
+
+fn block_from_bytes(bytes: &[u8]) -> Block {
+    let mut block = [0u64; BLOCK_WORDS];
+    for (word, chunk) in block.iter_mut().zip(bytes.chunks_exact(8)) {
+        let mut b = [0u8; 8];
+        b.copy_from_slice(chunk);
+        *word = u64::from_le_bytes(b);
+    }
+    block
+}
+
+struct Position {
+    pass: u32,
+    slice: u32,
+    index: u32,
+}
+
+fn reference_index(position: &Position, pseudo_rand: u64, lane_length: u32, segment_length: u32, same_lane: bool) -> u32 {
+    let reference_area_size = if position.pass == 0 {
+        if position.slice == 0 {
+            position.index - 1
+        } else if same_lane {
+            position.slice * segment_length + position.index - 1
+        } else {
+            position.slice * segment_length - if position.index == 0 { 1 } else { 0 }
+        }
+    } else if same_lane {
+        lane_length - segment_length + position.index - 1
+    } else {
+        lane_length - segment_length - if position.index == 0 { 1 } else { 0 }
+    };
+
+    let j1 = pseudo_rand & 0xffffffff;
+    let x = (j1 * j1) >> 32;
+    let y = (reference_area_size as u64 * x) >> 32;
+    let relative = reference_area_size as u64 - 1 - y;
+
+    let start = if position.pass != 0 && position.slice != SYNC_POINTS - 1 {
+        (position.slice + 1) * segment_length
+    } else {
+        0
+    };
+
+    ((start as u64 + relative) % lane_length as u64) as u32
+}
+
+pub fn argon2id_keyed(
+    password: &[u8],
+    salt: &[u8],
+    secret: &[u8],
+    associated_data: &[u8],
+    params: &Argon2Params,
+) -> Result<Vec<u8>, Error> {
+    if params.parallelism == 0 || params.parallelism > 0xffffff {
+        return Err(Error::Hash("Invalid Argon2 parallelism"));
+    }
+    if params.memory_kib < 8 * params.parallelism {
+        return Err(Error::Hash("Argon2 memory must be at least 8 KiB per lane"));
+    }
+    if params.iterations == 0 {
+        return Err(Error::Hash("Argon2 requires at least one iteration"));
+    }
+    if params.output_len < 4 || params.output_len > u32::MAX as usize {
+        return Err(Error::Hash("Invalid Argon2 output length"));
+    }
+    if salt.len() < 8 {
+        return Err(Error::Hash("Argon2 salt must be at least 8 bytes"));
+    }
+
+    let lanes = params.parallelism;
+    let segment_length = params.memory_kib / (SYNC_POINTS * lanes);
+    let lane_length = segment_length * SYNC_POINTS;
+    let block_count = lane_length * lanes;
+
+    let mut h0 = [0u8; MAX_OUTPUT_SIZE];
+    let mut hasher = Blake2b::new(MAX_OUTPUT_SIZE)?;
+    hasher.update(&lanes.to_le_bytes());
+    hasher.update(&(params.output_len as u32).to_le_bytes());
+    hasher.update(&params.memory_kib.to_le_bytes());
+    hasher.update(&params.iterations.to_le_bytes());
+    hasher.update(&VERSION.to_le_bytes());
+    hasher.update(&ARGON2ID.to_le_bytes());
+    for input in [password, salt, secret, associated_data] {
+        hasher.update(&(input.len() as u32).to_le_bytes());
+        hasher.update(input);
+    }
+    hasher.finalize_into(&mut h0)?;
+
+    let mut memory: Vec<Block> = Vec::new();
+    memory
+        .try_reserve_exact(block_count as usize)
+        .map_err(|_| Error::Hash("Unable to allocate Argon2 memory"))?;
+    memory.resize(block_count as usize, [0u64; BLOCK_WORDS]);
+    let block_at = |lane: u32, index: u32| (lane * lane_length + index) as usize;
+
+    let mut bytes = [0u8; BLOCK_BYTES];
+    for lane in 0..lanes {
+        for index in 0..2u32 {
+            blake2b_long(&[&h0, &index.to_le_bytes(), &lane.to_le_bytes()], &mut bytes)?;
+            memory[block_at(lane, index)] = block_from_bytes(&bytes);
+        }
+    }
+
+    let zero_block = [0u64; BLOCK_WORDS];
+
+    for pass in 0..params.iterations {
+        for slice in 0..SYNC_POINTS {
+            for lane in 0..lanes {
+                let data_independent = pass == 0 && slice < SYNC_POINTS / 2;
+
+                let mut input_block = [0u64; BLOCK_WORDS];
+                let mut address_block = [0u64; BLOCK_WORDS];
+                if data_independent {
+                    input_block[0] = pass as u64;
+                    input_block[1] = lane as u64;
+                    input_block[2] = slice as u64;
+                    input_block[3] = block_count as u64;
+                    input_block[4] = params.iterations as u64;
+                    input_block[5] = ARGON2ID as u64;
+                }
+
+                let first_index = if pass == 0 && slice == 0 { 2 } else { 0 };
+                if data_independent && first_index != 0 {
+                    input_block[6] += 1;
+                    address_block = compress(&zero_block, &compress(&zero_block, &input_block));
+                }
+
+                for index in first_index..segment_length {
+                    let current = slice * segment_length + index;
+                    let previous = if current == 0 { lane_length - 1 } else { current - 1 };
+
+                    let pseudo_rand = if data_independent {
+                        if index % BLOCK_WORDS as u32 == 0 {
+                            input_block[6] += 1;
+                            address_block = compress(&zero_block, &compress(&zero_block, &input_block));
+                        }
+                        address_block[(index % BLOCK_WORDS as u32) as usize]
+                    } else {
+                        memory[block_at(lane, previous)][0]
+                    };
+
+                    let reference_lane = if pass == 0 && slice == 0 {
+                        lane
+                    } else {
+                        ((pseudo_rand >> 32) % lanes as u64) as u32
+                    };
+
+                    let position = Position { pass, slice, index };
+                    let reference = reference_index(
+                        &position,
+                        pseudo_rand,
+                        lane_length,
+                        segment_length,
+                        reference_lane == lane,
+                    );
+
+                    let mut block = compress(
+                        &memory[block_at(lane, previous)],
+                        &memory[block_at(reference_lane, reference)],
+                    );
+                    if pass > 0 {
+                        let old = &memory[block_at(lane, current)];
+                        for i in 0..BLOCK_WORDS {
+                            block[i] ^= old[i];
+                        }
+                    }
+                    memory[block_at(lane, current)] = block;
+                }
+            }
+        }
+    }
+
+    let mut last = memory[block_at(0, lane_length - 1)];
+    for lane in 1..lanes {
+        let block = &memory[block_at(lane, lane_length - 1)];
+        for i in 0..BLOCK_WORDS {
+            last[i] ^= block[i];
+        }
+    }
+
+    for (chunk, word) in bytes.chunks_exact_mut(8).zip(last.iter()) {
+        chunk.copy_from_slice(&word.to_le_bytes());
+    }
+
+    let mut tag = vec![0u8; params.output_len];
+    blake2b_long(&[&bytes], &mut tag)?;
+    Ok(tag)
+}
+
+pub fn argon2id(password: &[u8], salt: &[u8], params: &Argon2Params) -> Result<Vec<u8>, Error> {
+    argon2id_keyed(password, salt, &[], &[], params)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // RFC9106 section 5.3.
+    #[test]
+    fn rfc9106_argon2id_vector() {
+        let params = Argon2Params { memory_kib: 32, iterations: 3, parallelism: 4, output_len: 32 };
+        let tag = argon2id_keyed(&[0x01; 32], &[0x02; 16], &[0x03; 8], &[0x04; 12], &params).unwrap();
+        assert_eq!(vec_to_hex(&tag), "0d640df58d78766c08c037a34a8b53c9d01ef0452d75b65eb52520e96b01e659");
+    }
+
+    // Cross-checked against OpenSSL's ARGON2ID KDF.
+    #[test]
+    fn variable_output_length() {
+        let cases = [
+            (4, "c78667e0"),
+            (65, "47dd018cef0879e5138f39930a98e6d26e0b0a733fe1cbe9f91d69a98619c1dcfa9cc190692b23e446533910e0a72cf821efd4971f049db22e186abdfb499f0f96"),
+            (100, "16d4a732b220b8ef2b98f7f7f71823ef82343edee48542bf59309d83ab6a61ee3cee116336928512ac7e9c044bf48426dfc5c6380a5a4156a6f4f5c9f92c523b53cf8ec452c2455af4878016e7bd2820c971e76e3d0c4fecbed31016c7950b8f1fe7462b"),
+        ];
+        for (output_len, expected) in cases {
+            let params = Argon2Params { memory_kib: 64, iterations: 1, parallelism: 1, output_len };
+            assert_eq!(vec_to_hex(&argon2id(b"password", b"somesaltsalt", &params).unwrap()), expected);
+        }
+    }
+
+    #[test]
+    fn invalid_parameters() {
+        let params = Argon2Params { memory_kib: 32, iterations: 1, parallelism: 1, output_len: 32 };
+        assert!(argon2id(b"password", b"short", &params).is_err());
+        assert!(argon2id(b"password", b"somesaltsalt", &Argon2Params { iterations: 0, ..params }).is_err());
+        assert!(argon2id(b"password", b"somesaltsalt", &Argon2Params { parallelism: 0, ..params }).is_err());
+        assert!(argon2id(b"password", b"somesaltsalt", &Argon2Params { memory_kib: 7, ..params }).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+use std::array::from_fn;
+
+use crate::hash::{ripemd160, sha256};
+
+const LANES: usize = 8;
+
+type Lanes = [u32; LANES];
+
+fn padded_len(message_len: usize) -> usize {
+    (message_len + 9).div_ceil(64) * 64
+}
+
+fn pad_lanes<const N: usize>(inputs: &[[u8; N]], big_endian: bool, buffer: &mut [u8]) {
+    let block_len = buffer.len() / LANES;
+    let bit_len = (N as u64) * 8;
+    let length_bytes = if big_endian { bit_len.to_be_bytes() } else { bit_len.to_le_bytes() };
+
+    buffer.fill(0);
+    for (lane, input) in inputs.iter().enumerate() {
+        let padded = &mut buffer[lane * block_len..(lane + 1) * block_len];
+        padded[..N].copy_from_slice(input);
+        padded[N] = 0x80;
+        padded[block_len - 8..].copy_from_slice(&length_bytes);
+    }
+}
+
+fn load_words(buffer: &[u8], block_len: usize, offset: usize, big_endian: bool) -> [Lanes; 16] {
+    from_fn(|i| {
+        from_fn(|lane| {
+            let j = lane * block_len + offset + i * 4;
+            let bytes = [buffer[j], buffer[j + 1], buffer[j + 2], buffer[j + 3]];
+            if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) }
+        })
+    })
+}
+
+fn sha256_compress_lanes(state: &mut [Lanes; 8], block: &[Lanes; 16]) {
+    let mut w = [[0u32; LANES]; 64];
+    w[..16].copy_from_slice(block);
+    for i in 16..64 {
+        let (w2, w7, w15, w16) = (w[i - 2], w[i - 7], w[i - 15], w[i - 16]);
+        for l in 0..LANES {
+            let s0 = w15[l].rotate_right(7) ^ w15[l].rotate_right(18) ^ (w15[l] >> 3);
+            let s1 = w2[l].rotate_right(17) ^ w2[l].rotate_right(19) ^ (w2[l] >> 10);
+            w[i][l] = w16[l]
+                .wrapping_add(s0)
+                .wrapping_add(w7[l])
+                .wrapping_add(s1);
+        }
+    }
+
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut hh] = *state;
+
+    for (k, wi) in sha256::K.iter().zip(w.iter()) {
+        for l in 0..LANES {
+            let s1 = e[l].rotate_right(6) ^ e[l].rotate_right(11) ^ e[l].rotate_right(25);
+            let ch = (e[l] & f[l]) ^ ((!e[l]) & g[l]);
+            let temp1 = hh[l].wrapping_add(s1)
+                .wrapping_add(ch)
+                .wrapping_add(*k)
+                .wrapping_add(wi[l]);
+
+            let s0 = a[l].rotate_right(2) ^ a[l].rotate_right(13) ^ a[l].rotate_right(22);
+            let maj = (a[l] & b[l]) ^ (a[l] & c[l]) ^ (b[l] & c[l]);
+            let temp2 = s0.wrapping_add(maj);
+
+            hh[l] = g[l];
+            g[l] = f[l];
+            f[l] = e[l];
+            e[l] = d[l].wrapping_add(temp1);
+            d[l] = c[l];
+            c[l] = b[l];
+            b[l] = a[l];
+            a[l] = temp1.wrapping_add(temp2);
+        }
+    }
+
+    for (s, v) in state.iter_mut().zip([a, b, c, d, e, f, g, hh]) {
+        for l in 0..LANES {
+            s[l] = s[l].wrapping_add(v[l]);
+        }
+    }
+}
+
+fn ripemd160_f(round: usize, x: u32, y: u32, z: u32) -> u32 {
+    match round {
+        0 => x ^ y ^ z,
+        1 => (x & y) | (!x & z),
+        2 => (x | !y) ^ z,
+        3 => (x & z) | (y & !z),
+        _ => x ^ (y | !z),
+    }
+}
+
+fn ripemd160_rounds<const ROUND: usize>(
+    left: &mut [Lanes; 5],
+    right: &mut [Lanes; 5],
+    x: &[Lanes; 16],
+) {
+    let [a, b, c, d, e] = left;
+    let [a_prime, b_prime, c_prime, d_prime, e_prime] = right;
+
+    for j in ROUND * 16..(ROUND + 1) * 16 {
+        let xr = &x[ripemd160::R[j]];
+        let xr_prime = &x[ripemd160::R_PRIME[j]];
+
+        for l in 0..LANES {
+            let temp = a[l]
+                .wrapping_add(ripemd160_f(ROUND, b[l], c[l], d[l]))
+                .wrapping_add(xr[l])
+                .wrapping_add(ripemd160::K[ROUND])
+                .rotate_left(ripemd160::S[j])
+                .wrapping_add(e[l]);
+            a[l] = e[l];
+            e[l] = d[l];
+            d[l] = c[l].rotate_left(10);
+            c[l] = b[l];
+            b[l] = temp;
+
+            let temp_prime = a_prime[l]
+                .wrapping_add(ripemd160_f(4 - ROUND, b_prime[l], c_prime[l], d_prime[l]))
+                .wrapping_add(xr_prime[l])
+                .wrapping_add(ripemd160::K_PRIME[ROUND])
+                .rotate_left(ripemd160::S_PRIME[j])
+                .wrapping_add(e_prime[l]);
+            a_prime[l] = e_prime[l];
+            e_prime[l] = d_prime[l];
+            d_prime[l] = c_prime[l].rotate_left(10);
+            c_prime[l] = b_prime[l];
+            b_prime[l] = temp_prime;
+        }
+    }
+}
+
+fn ripemd160_compress_lanes(state: &mut [Lanes; 5], x: &[Lanes; 16]) {
+    let mut left = *state;
+    let mut right = *state;
+
+    ripemd160_rounds::<0>(&mut left, &mut right, x);
+    ripemd160_rounds::<1>(&mut left, &mut right, x);
+    ripemd160_rounds::<2>(&mut left, &mut right, x);
+    ripemd160_rounds::<3>(&mut left, &mut right, x);
+    ripemd160_rounds::<4>(&mut left, &mut right, x);
+
+    let [a, b, c, d, e] = left;
+    let [a_prime, b_prime, c_prime, d_prime, e_prime] = right;
+
+    for l in 0..LANES {
+        let t = state[1][l].wrapping_add(c[l]).wrapping_add(d_prime[l]);
+        state[1][l] = state[2][l].wrapping_add(d[l]).wrapping_add(e_prime[l]);
+        state[2][l] = state[3][l].wrapping_add(e[l]).wrapping_add(a_prime[l]);
+        state[3][l] = state[4][l].wrapping_add(a[l]).wrapping_add(b_prime[l]);
+        state[4][l] = state[0][l].wrapping_add(b[l]).wrapping_add(c_prime[l]);
+        state[0][l] = t;
+    }
+}
+
+fn sha256_lanes<const N: usize>(inputs: &[[u8; N]], buffer: &mut [u8]) -> [Lanes; 8] {
+    let block_len = buffer.len() / LANES;
+    pad_lanes(inputs, true, buffer);
+
+    let mut state = sha256::H.map(|word| [word; LANES]);
+    for offset in (0..block_len).step_by(64) {
+        let block = load_words(buffer, block_len, offset, true);
+        sha256_compress_lanes(&mut state, &block);
+    }
+    state
+}
+
+fn ripemd160_lanes<const N: usize>(inputs: &[[u8; N]], buffer: &mut [u8]) -> [Lanes; 5] {
+    let block_len = buffer.len() / LANES;
+    pad_lanes(inputs, false, buffer);
+
+    let mut state = ripemd160::H.map(|word| [word; LANES]);
+    for offset in (0..block_len).step_by(64) {
+        let block = load_words(buffer, block_len, offset, false);
+        ripemd160_compress_lanes(&mut state, &block);
+    }
+    state
+}
+
+fn sha256_digests(state: &[Lanes; 8], count: usize) -> [[u8; 32]; LANES] {
+    let mut digests = [[0u8; 32]; LANES];
+    for (lane, digest) in digests.iter_mut().enumerate().take(count) {
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(state.iter()) {
+            chunk.copy_from_slice(&word[lane].to_be_bytes());
+        }
+    }
+    digests
+}
+
+fn ripemd160_digests(state: &[Lanes; 5], count: usize) -> [[u8; 20]; LANES] {
+    let mut digests = [[0u8; 20]; LANES];
+    for (lane, digest) in digests.iter_mut().enumerate().take(count) {
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(state.iter()) {
+            chunk.copy_from_slice(&word[lane].to_le_bytes());
+        }
+    }
+    digests
+}
+
+pub fn sha256_many<const N: usize>(inputs: &[[u8; N]]) -> Vec<[u8; 32]> {
+    let mut buffer = vec![0u8; padded_len(N) * LANES];
+    let mut output = Vec::with_capacity(inputs.len());
+
+    for group in inputs.chunks(LANES) {
+        let state = sha256_lanes(group, &mut buffer);
+        output.extend_from_slice(&sha256_digests(&state, group.len())[..group.len()]);
+    }
+    output
+}
+
+pub fn ripemd160_many<const N: usize>(inputs: &[[u8; N]]) -> Vec<[u8; 20]> {
+    let mut buffer = vec![0u8; padded_len(N) * LANES];
+    let mut output = Vec::with_capacity(inputs.len());
+
+    for group in inputs.chunks(LANES) {
+        let state = ripemd160_lanes(group, &mut buffer);
+        output.extend_from_slice(&ripemd160_digests(&state, group.len())[..group.len()]);
+    }
+    output
+}
+
+pub fn hash160_many<const N: usize>(inputs: &[[u8; N]]) -> Vec<[u8; 20]> {
+    let mut sha_buffer = vec![0u8; padded_len(N) * LANES];
+    let mut ripemd_buffer = vec![0u8; padded_len(32) * LANES];
+    let mut output = Vec::with_capacity(inputs.len());
+
+    for group in inputs.chunks(LANES) {
+        let sha_state = sha256_lanes(group, &mut sha_buffer);
+        let sha_digests = sha256_digests(&sha_state, group.len());
+
+        let ripemd_state = ripemd160_lanes(&sha_digests[..group.len()], &mut ripemd_buffer);
+        output.extend_from_slice(&ripemd160_digests(&ripemd_state, group.len())[..group.len()]);
+    }
+    output
+}
+
+pub fn double_sha256_many<const N: usize>(inputs: &[[u8; N]]) -> Vec<[u8; 32]> {
+    let mut first_buffer = vec![0u8; padded_len(N) * LANES];
+    let mut second_buffer = vec![0u8; padded_len(32) * LANES];
+    let mut output = Vec::with_capacity(inputs.len());
+
+    for group in inputs.chunks(LANES) {
+        let first_state = sha256_lanes(group, &mut first_buffer);
+        let first_digests = sha256_digests(&first_state, group.len());
+
+        let second_state = sha256_lanes(&first_digests[..group.len()], &mut second_buffer);
+        output.extend_from_slice(&sha256_digests(&second_state, group.len())[..group.len()]);
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{double_sha256, hash160};
+
+    fn check_matches_scalar<const N: usize>() {
+        for count in [0, 1, 7, 8, 9, 17] {
+            let inputs: Vec<[u8; N]> = (0..count)
+                .map(|i| from_fn(|j| (i * 31 + j * 7 + N) as u8))
+                .collect();
+
+            let expected: Vec<[u8; 32]> = inputs.iter().map(|input| sha256(input)).collect();
+            assert_eq!(sha256_many(&inputs), expected);
+
+            let expected: Vec<[u8; 32]> = inputs.iter().map(|input| double_sha256(input)).collect();
+            assert_eq!(double_sha256_many(&inputs), expected);
+
+            let expected: Vec<[u8; 20]> = inputs.iter().map(|input| ripemd160(input)).collect();
+            assert_eq!(ripemd160_many(&inputs), expected);
+
+            let expected: Vec<[u8; 20]> = inputs.iter().map(|input| hash160(input)).collect();
+            assert_eq!(hash160_many(&inputs), expected);
+        }
+    }
+
+    #[test]
+    fn matches_scalar_functions() {
+        check_matches_scalar::<0>();
+        check_matches_scalar::<33>();
+        check_matches_scalar::<55>();
+        check_matches_scalar::<56>();
+        check_matches_scalar::<64>();
+        check_matches_scalar::<65>();
+        check_matches_scalar::<200>();
+    }
+}
@@ -0,0 +1,180 @@
+use crate::error::Error;
+
+const IV: [u64; 8] = [
+    0x6a09e667f3bcc908,
+    0xbb67ae8584caa73b,
+    0x3c6ef372fe94f82b,
+    0xa54ff53a5f1d36f1,
+    0x510e527fade682d1,
+    0x9b05688c2b3e6c1f,
+    0x1f83d9abfb41bd6b,
+    0x5be0cd19137e2179
+];
+
+const SIGMA: [[usize; 16]; 12] = [
+    [ 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15],
+    [14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3],
+    [11,  8, 12,  0,  5,  2, 15, 13, 10, 14,  3,  6,  7,  1,  9,  4],
+    [ 7,  9,  3,  1, 13, 12, 11, 14,  2,  6,  5, 10,  4,  0, 15,  8],
+    [ 9,  0,  5,  7,  2,  4, 10, 15, 14,  1, 11, 12,  6,  8,  3, 13],
+    [ 2, 12,  6, 10,  0, 11,  8,  3,  4, 13,  7,  5, 15, 14,  1,  9],
+    [12,  5,  1, 15, 14, 13,  4, 10,  0,  7,  6,  3,  9,  2,  8, 11],
+    [13, 11,  7, 14, 12,  1,  3,  9,  5,  0, 15,  4,  8,  6,  2, 10],
+    [ 6, 15, 14,  9, 11,  3,  0,  8, 12,  2, 13,  7,  1,  4, 10,  5],
+    [10,  2,  8,  4,  7,  6,  1,  5, 15, 11,  9, 14,  3, 12, 13,  0],
+    [ 0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15],
+    [14, 10,  4,  8,  9, 15, 13,  6,  1, 12,  0,  2, 11,  7,  5,  3],
+];
+
+pub const MAX_OUTPUT_SIZE: usize = 64;
+
+fn mix(v: &mut [u64; 16], a: usize, b: usize, c: usize, d: usize, x: u64, y: u64) {
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(x);
+    v[d] = (v[d] ^ v[a]).rotate_right(32);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(24);
+    v[a] = v[a].wrapping_add(v[b]).wrapping_add(y);
+    v[d] = (v[d] ^ v[a]).rotate_right(16);
+    v[c] = v[c].wrapping_add(v[d]);
+    v[b] = (v[b] ^ v[c]).rotate_right(63);
+}
+
+fn compress_block(h: &mut [u64; 8], block: &[u8], counter: u128, last: bool) {
+    let mut m = [0u64; 16];
+    for (i, word) in m.iter_mut().enumerate() {
+        let mut bytes = [0u8; 8];
+        bytes.copy_from_slice(&block[i * 8..i * 8 + 8]);
+        *word = u64::from_le_bytes(bytes);
+    }
+
+    let mut v = [0u64; 16];
+    v[..8].copy_from_slice(h);
+    v[8..].copy_from_slice(&IV);
+    v[12] ^= counter as u64;
+    v[13] ^= (counter >> 64) as u64;
+    if last {
+        v[14] = !v[14];
+    }
+
+    for s in SIGMA.iter() {
+        mix(&mut v, 0, 4,  8, 12, m[s[0]],  m[s[1]]);
+        mix(&mut v, 1, 5,  9, 13, m[s[2]],  m[s[3]]);
+        mix(&mut v, 2, 6, 10, 14, m[s[4]],  m[s[5]]);
+        mix(&mut v, 3, 7, 11, 15, m[s[6]],  m[s[7]]);
+        mix(&mut v, 0, 5, 10, 15, m[s[8]],  m[s[9]]);
+        mix(&mut v, 1, 6, 11, 12, m[s[10]], m[s[11]]);
+        mix(&mut v, 2, 7,  8, 13, m[s[12]], m[s[13]]);
+        mix(&mut v, 3, 4,  9, 14, m[s[14]], m[s[15]]);
+    }
+
+    for i in 0..8 {
+        h[i] ^= v[i] ^ v[i + 8];
+    }
+}
+
+#[derive(Clone)]
+pub struct Blake2b {
+    state: [u64; 8],
+    buffer: [u8; 128],
+    buffer_len: usize,
+    counter: u128,
+    output_len: usize,
+}
+
+impl Blake2b {
+    pub fn new(output_len: usize) -> Result<Self, Error> {
+        if output_len == 0 || output_len > MAX_OUTPUT_SIZE {
+            return Err(Error::Hash("Invalid BLAKE2b output length"));
+        }
+
+        let mut state = IV;
+        state[0] ^= 0x01010000 ^ (output_len as u64);
+
+        Ok(Blake2b {
+            state,
+            buffer: [0u8; 128],
+            buffer_len: 0,
+            counter: 0,
+            output_len,
+        })
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        while !data.is_empty() {
+            if self.buffer_len == 128 {
+                self.counter += 128;
+                compress_block(&mut self.state, &self.buffer, self.counter, false);
+                self.buffer_len = 0;
+            }
+
+            let take = (128 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+        }
+    }
+
+    pub fn finalize_into(mut self, output: &mut [u8]) -> Result<(), Error> {
+        if output.len() != self.output_len {
+            return Err(Error::Hash("Output buffer length mismatch"));
+        }
+
+        self.counter += self.buffer_len as u128;
+        self.buffer[self.buffer_len..].fill(0);
+        compress_block(&mut self.state, &self.buffer, self.counter, true);
+
+        let mut digest = [0u8; MAX_OUTPUT_SIZE];
+        for (chunk, word) in digest.chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        output.copy_from_slice(&digest[..self.output_len]);
+        Ok(())
+    }
+}
+
+pub fn blake2b(output_len: usize, message: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut output = vec![0u8; output_len];
+    let mut hasher = Blake2b::new(output_len)?;
+    hasher.update(message);
+    hasher.finalize_into(&mut output)?;
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // RFC7693 appendix A.
+    #[test]
+    fn rfc7693_vector() {
+        assert_eq!(
+            vec_to_hex(&blake2b(64, b"abc").unwrap()),
+            "ba80a53f981c4d0d6a2797b69f12f6e94c212f14685ac4b74b12bb6fdbffa2d17d87c5392aab792dc252d5de4533cc9518d38aa8dbf1925ab92386edd4009923"
+        );
+        assert_eq!(
+            vec_to_hex(&blake2b(64, b"").unwrap()),
+            "786a02f742015903c6c6fd852552d272912f4740e15847618a86e217f71f5419d25e1031afee585313896444934eb04b903a685b1448b755d56f701afe9be2ce"
+        );
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let message: Vec<u8> = (0..=255u8).cycle().take(300).collect();
+        for split in [0, 1, 127, 128, 129, 256, 300] {
+            let mut hasher = Blake2b::new(32).unwrap();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..]);
+            let mut output = [0u8; 32];
+            hasher.finalize_into(&mut output).unwrap();
+            assert_eq!(output.to_vec(), blake2b(32, &message).unwrap());
+        }
+    }
+
+    #[test]
+    fn invalid_output_length() {
+        assert!(Blake2b::new(0).is_err());
+        assert!(Blake2b::new(MAX_OUTPUT_SIZE + 1).is_err());
+        assert!(Blake2b::new(32).unwrap().finalize_into(&mut [0u8; 64]).is_err());
+    }
+}
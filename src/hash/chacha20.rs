@@ -0,0 +1,96 @@
+use crate::error::Error;
+
+const CONSTANTS: [u32; 4] = [0x61707865, 0x3320646e, 0x79622d32, 0x6b206574];
+
+fn quarter_round(state: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(16);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(12);
+    state[a] = state[a].wrapping_add(state[b]);
+    state[d] = (state[d] ^ state[a]).rotate_left(8);
+    state[c] = state[c].wrapping_add(state[d]);
+    state[b] = (state[b] ^ state[c]).rotate_left(7);
+}
+
+pub fn chacha20_block(key: &[u8; 32], counter: u32, nonce: &[u8; 12]) -> [u8; 64] {
+    let mut state = [0u32; 16];
+    state[..4].copy_from_slice(&CONSTANTS);
+    for i in 0..8 {
+        state[4 + i] = u32::from_le_bytes([key[i * 4], key[i * 4 + 1], key[i * 4 + 2], key[i * 4 + 3]]);
+    }
+    state[12] = counter;
+    for i in 0..3 {
+        state[13 + i] = u32::from_le_bytes([nonce[i * 4], nonce[i * 4 + 1], nonce[i * 4 + 2], nonce[i * 4 + 3]]);
+    }
+
+    let mut working = state;
+    for _ in 0..10 {
+        quarter_round(&mut working, 0, 4, 8, 12);
+        quarter_round(&mut working, 1, 5, 9, 13);
+        quarter_round(&mut working, 2, 6, 10, 14);
+        quarter_round(&mut working, 3, 7, 11, 15);
+        quarter_round(&mut working, 0, 5, 10, 15);
+        quarter_round(&mut working, 1, 6, 11, 12);
+        quarter_round(&mut working, 2, 7, 8, 13);
+        quarter_round(&mut working, 3, 4, 9, 14);
+    }
+
+    let mut output = [0u8; 64];
+    for (i, chunk) in output.chunks_exact_mut(4).enumerate() {
+        chunk.copy_from_slice(&working[i].wrapping_add(state[i]).to_le_bytes());
+    }
+    output
+}
+
+pub fn chacha20_xor(key: &[u8; 32], counter: u32, nonce: &[u8; 12], data: &mut [u8]) -> Result<(), Error> {
+    let blocks = data.len().div_ceil(64);
+    if blocks > 0 {
+        u32::try_from(blocks - 1)
+            .ok()
+            .and_then(|last| counter.checked_add(last))
+            .ok_or(Error::Hash("ChaCha20 block counter overflow"))?;
+    }
+
+    for (i, chunk) in data.chunks_mut(64).enumerate() {
+        let keystream = chacha20_block(key, counter + i as u32, nonce);
+        for (byte, k) in chunk.iter_mut().zip(keystream.iter()) {
+            *byte ^= k;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    // RFC8439 section 2.3.2.
+    #[test]
+    fn rfc8439_block() {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&hex_to_vec("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f").unwrap());
+        let mut nonce = [0u8; 12];
+        nonce.copy_from_slice(&hex_to_vec("000000090000004a00000000").unwrap());
+
+        assert_eq!(
+            vec_to_hex(&chacha20_block(&key, 1, &nonce)),
+            "10f1e7e4d13b5915500fdd1fa32071c4c7d1f4c733c068030422aa9ac3d46c4ed2826446079faa0914c2d705d98b02a2b5129cd1de164eb9cbd083e8a2503c4e"
+        );
+    }
+
+    #[test]
+    fn counter_overflow_is_rejected() {
+        let key = [0x42u8; 32];
+        let nonce = [0x24u8; 12];
+
+        let mut last_block = [0u8; 64];
+        assert!(chacha20_xor(&key, u32::MAX, &nonce, &mut last_block).is_ok());
+        assert_eq!(last_block, chacha20_block(&key, u32::MAX, &nonce));
+
+        let mut data = [0u8; 65];
+        assert!(chacha20_xor(&key, u32::MAX, &nonce, &mut data).is_err());
+        assert_eq!(data, [0u8; 65]);
+    }
+}
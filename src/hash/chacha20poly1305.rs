@@ -0,0 +1,128 @@
+use crate::hash::chacha20::{chacha20_block, chacha20_xor};
+use crate::hash::poly1305::Poly1305;
+use crate::error::Error;
+
+pub const KEY_SIZE: usize = 32;
+pub const NONCE_SIZE: usize = 12;
+pub const TAG_SIZE: usize = 16;
+pub const MAX_PLAINTEXT_SIZE: u64 = u32::MAX as u64 * 64;
+
+#[derive(Clone)]
+pub struct ChaCha20Poly1305 {
+    key: [u8; KEY_SIZE],
+}
+
+impl ChaCha20Poly1305 {
+    pub fn new(key: &[u8; KEY_SIZE]) -> Self {
+        ChaCha20Poly1305 { key: *key }
+    }
+
+    fn compute_tag(&self, nonce: &[u8; NONCE_SIZE], aad: &[u8], ciphertext: &[u8]) -> [u8; TAG_SIZE] {
+        let block = chacha20_block(&self.key, 0, nonce);
+        let mut otk = [0u8; 32];
+        otk.copy_from_slice(&block[..32]);
+
+        let zeros = [0u8; 16];
+        let mut mac = Poly1305::new(&otk);
+        mac.update(aad);
+        mac.update(&zeros[..(16 - aad.len() % 16) % 16]);
+        mac.update(ciphertext);
+        mac.update(&zeros[..(16 - ciphertext.len() % 16) % 16]);
+        mac.update(&(aad.len() as u64).to_le_bytes());
+        mac.update(&(ciphertext.len() as u64).to_le_bytes());
+        mac.finalize()
+    }
+
+    pub fn seal(&self, nonce: &[u8; NONCE_SIZE], aad: &[u8], plaintext: &[u8]) -> Result<Vec<u8>, Error> {
+        if plaintext.len() as u64 > MAX_PLAINTEXT_SIZE {
+            return Err(Error::Hash("Plaintext too large for a single nonce"));
+        }
+
+        let mut sealed = Vec::with_capacity(plaintext.len() + TAG_SIZE);
+        sealed.extend_from_slice(plaintext);
+        chacha20_xor(&self.key, 1, nonce, &mut sealed)?;
+
+        let tag = self.compute_tag(nonce, aad, &sealed);
+        sealed.extend_from_slice(&tag);
+        Ok(sealed)
+    }
+
+    pub fn open(&self, nonce: &[u8; NONCE_SIZE], aad: &[u8], sealed: &[u8]) -> Result<Vec<u8>, Error> {
+        if sealed.len() < TAG_SIZE {
+            return Err(Error::Hash("Sealed data too short"));
+        }
+
+        let (ciphertext, tag) = sealed.split_at(sealed.len() - TAG_SIZE);
+        let expected = self.compute_tag(nonce, aad, ciphertext);
+
+        let mut diff = 0u8;
+        for (a, b) in expected.iter().zip(tag.iter()) {
+            diff |= a ^ b;
+        }
+        if diff != 0 {
+            return Err(Error::Hash("Invalid authentication tag"));
+        }
+
+        let mut plaintext = ciphertext.to_vec();
+        chacha20_xor(&self.key, 1, nonce, &mut plaintext)?;
+        Ok(plaintext)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    const PLAINTEXT: &[u8] = b"Ladies and Gentlemen of the class of '99: If I could offer you only one tip for the future, sunscreen would be it.";
+
+    fn rfc8439_cipher() -> (ChaCha20Poly1305, [u8; NONCE_SIZE], Vec<u8>) {
+        let mut key = [0u8; KEY_SIZE];
+        key.copy_from_slice(&hex_to_vec("808182838485868788898a8b8c8d8e8f909192939495969798999a9b9c9d9e9f").unwrap());
+        let mut nonce = [0u8; NONCE_SIZE];
+        nonce.copy_from_slice(&hex_to_vec("070000004041424344454647").unwrap());
+        let aad = hex_to_vec("50515253c0c1c2c3c4c5c6c7").unwrap();
+        (ChaCha20Poly1305::new(&key), nonce, aad)
+    }
+
+    // RFC8439 section 2.8.2.
+    #[test]
+    fn rfc8439_aead_vector() {
+        let (cipher, nonce, aad) = rfc8439_cipher();
+
+        let sealed = cipher.seal(&nonce, &aad, PLAINTEXT).unwrap();
+        let (ciphertext, tag) = sealed.split_at(PLAINTEXT.len());
+        assert_eq!(
+            vec_to_hex(ciphertext),
+            "d31a8d34648e60db7b86afbc53ef7ec2a4aded51296e08fea9e2b5a736ee62d63dbea45e8ca9671282fafb69da92728b1a71de0a9e060b2905d6a5b67ecd3b3692ddbd7f2d778b8c9803aee328091b58fab324e4fad675945585808b4831d7bc3ff4def08e4b7a9de576d26586cec64b6116"
+        );
+        assert_eq!(vec_to_hex(tag), "1ae10b594f09e26a7e902ecbd0600691");
+
+        assert_eq!(cipher.open(&nonce, &aad, &sealed).unwrap(), PLAINTEXT);
+    }
+
+    #[test]
+    fn open_rejects_tampering() {
+        let (cipher, nonce, aad) = rfc8439_cipher();
+        let sealed = cipher.seal(&nonce, &aad, PLAINTEXT).unwrap();
+
+        let mut bad_tag = sealed.clone();
+        *bad_tag.last_mut().unwrap() ^= 0x01;
+        assert!(cipher.open(&nonce, &aad, &bad_tag).is_err());
+
+        let mut bad_ciphertext = sealed.clone();
+        bad_ciphertext[0] ^= 0x01;
+        assert!(cipher.open(&nonce, &aad, &bad_ciphertext).is_err());
+
+        assert!(cipher.open(&nonce, b"other aad", &sealed).is_err());
+        assert!(cipher.open(&nonce, &aad, &sealed[..TAG_SIZE - 1]).is_err());
+    }
+
+    #[test]
+    fn empty_plaintext() {
+        let (cipher, nonce, aad) = rfc8439_cipher();
+        let sealed = cipher.seal(&nonce, &aad, &[]).unwrap();
+        assert_eq!(sealed.len(), TAG_SIZE);
+        assert!(cipher.open(&nonce, &aad, &sealed).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use crate::hash::{Hasher, Hmac, Sha256, hmac};
+use crate::error::Error;
+
+pub fn hkdf_extract<H: Hasher>(salt: &[u8], ikm: &[u8]) -> H::Output {
+    hmac::<H>(salt, ikm)
+}
+
+pub fn hkdf_expand<H: Hasher>(prk: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+    if length > 255 * H::OUTPUT_SIZE {
+        return Err(Error::Hash("HKDF output length too large"));
+    }
+
+    let prf = Hmac::<H>::new(prk);
+
+    let mut okm = Vec::with_capacity(length);
+    let mut previous: Option<H::Output> = None;
+    let mut counter: u8 = 1;
+
+    while okm.len() < length {
+        let mut mac = prf.clone();
+        if let Some(t) = &previous {
+            mac.update(t.as_ref());
+        }
+        mac.update(info);
+        mac.update(&[counter]);
+
+        let t = mac.finalize();
+        okm.extend_from_slice(t.as_ref());
+        previous = Some(t);
+        counter = counter.wrapping_add(1);
+    }
+
+    okm.truncate(length);
+    Ok(okm)
+}
+
+pub fn hkdf<H: Hasher>(salt: &[u8], ikm: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+    let prk = hkdf_extract::<H>(salt, ikm);
+    hkdf_expand::<H>(prk.as_ref(), info, length)
+}
+
+pub fn hkdf_sha256(salt: &[u8], ikm: &[u8], info: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+    hkdf::<Sha256>(salt, ikm, info, length)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    #[test]
+    fn rfc5869_case_1() {
+        let ikm = [0x0b; 22];
+        let salt = hex_to_vec("000102030405060708090a0b0c").unwrap();
+        let info = hex_to_vec("f0f1f2f3f4f5f6f7f8f9").unwrap();
+
+        let prk = hkdf_extract::<Sha256>(&salt, &ikm);
+        assert_eq!(vec_to_hex(&prk), "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5");
+        assert_eq!(
+            vec_to_hex(&hkdf_sha256(&salt, &ikm, &info, 42).unwrap()),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+    }
+
+    #[test]
+    fn rfc5869_case_3() {
+        let ikm = [0x0b; 22];
+
+        let prk = hkdf_extract::<Sha256>(&[], &ikm);
+        assert_eq!(vec_to_hex(&prk), "19ef24a32c717b167f33a91d6f648bdf96596776afdb6377ac434c1c293ccb04");
+        assert_eq!(
+            vec_to_hex(&hkdf_sha256(&[], &ikm, &[], 42).unwrap()),
+            "8da4e775a563c18f715f802a063c5a31b8a11f5c5ee1879ec3454e5f3c738d2d9d201395faa4b61a96c8"
+        );
+    }
+
+    #[test]
+    fn output_length_limit() {
+        assert_eq!(hkdf_sha256(&[], &[1], &[], 255 * 32).unwrap().len(), 255 * 32);
+        assert!(hkdf_sha256(&[], &[1], &[], 255 * 32 + 1).is_err());
+    }
+}
@@ -1,31 +1,143 @@
-use crate::hash::sha512;
+use crate::hash::{Hasher, Sha256, Sha512};
 
-pub fn hmac_sha512(key: &[u8], message: &[u8]) -> Vec<u8> {
-    const BLOCK_SIZE: usize = 128;
+const MAX_BLOCK_SIZE: usize = 128;
 
-    let mut key = if key.len() > BLOCK_SIZE {
-        sha512(key)
-    } else {
-        key.to_vec()
-    };
+#[derive(Clone)]
+pub struct Hmac<H: Hasher> {
+    inner: H,
+    outer: H,
+}
+
+pub type HmacSha256 = Hmac<Sha256>;
+pub type HmacSha512 = Hmac<Sha512>;
+
+impl<H: Hasher> Hmac<H> {
+    pub fn new(key: &[u8]) -> Self {
+        let block_size = H::BLOCK_SIZE;
+
+        let mut key_block = [0u8; MAX_BLOCK_SIZE];
+        if key.len() > block_size {
+            key_block[..H::OUTPUT_SIZE].copy_from_slice(H::digest(key).as_ref());
+        } else {
+            key_block[..key.len()].copy_from_slice(key);
+        }
+
+        let mut ipad = [0x36; MAX_BLOCK_SIZE];
+        let mut opad = [0x5c; MAX_BLOCK_SIZE];
+
+        for i in 0..block_size {
+            ipad[i] ^= key_block[i];
+            opad[i] ^= key_block[i];
+        }
+
+        let mut inner = H::new();
+        inner.update(&ipad[..block_size]);
 
-    if key.len() < BLOCK_SIZE {
-        key.resize(BLOCK_SIZE, 0x00);
+        let mut outer = H::new();
+        outer.update(&opad[..block_size]);
+
+        Hmac { inner, outer }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        self.inner.update(data);
     }
 
-    let mut ipad = vec![0x36; BLOCK_SIZE];
-    let mut opad = vec![0x5c; BLOCK_SIZE];
+    pub fn finalize(self) -> H::Output {
+        let inner_hash = self.inner.finalize();
 
-    for i in 0..BLOCK_SIZE {
-        ipad[i] ^= key[i];
-        opad[i] ^= key[i];
+        let mut outer = self.outer;
+        outer.update(inner_hash.as_ref());
+        outer.finalize()
     }
+}
 
-    let mut inner_hash_input = ipad;
-    inner_hash_input.extend_from_slice(message);
-    let inner_hash = sha512(&inner_hash_input);
+pub fn hmac<H: Hasher>(key: &[u8], message: &[u8]) -> H::Output {
+    let mut mac = Hmac::<H>::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+pub fn hmac_sha256(key: &[u8], message: &[u8]) -> [u8; 32] {
+    hmac::<Sha256>(key, message)
+}
 
-    let mut outer_hash_input = opad;
-    outer_hash_input.extend_from_slice(&inner_hash);
-    sha512(&outer_hash_input)
+pub fn hmac_sha512(key: &[u8], message: &[u8]) -> [u8; 64] {
+    hmac::<Sha512>(key, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // RFC4231 test cases 1, 2, 6 and 7.
+    #[test]
+    fn rfc4231_vectors() {
+        let cases: [(&[u8], &[u8], &str, &str); 4] = [
+            (
+                &[0x0b; 20],
+                b"Hi There",
+                "b0344c61d8db38535ca8afceaf0bf12b881dc200c9833da726e9376c2e32cff7",
+                "87aa7cdea5ef619d4ff0b4241a1d6cb02379f4e2ce4ec2787ad0b30545e17cdedaa833b7d6b8a702038b274eaea3f4e4be9d914eeb61f1702e696c203a126854",
+            ),
+            (
+                b"Jefe",
+                b"what do ya want for nothing?",
+                "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843",
+                "164b7a7bfcf819e2e395fbe73b56e0a387bd64222e831fd610270cd7ea2505549758bf75c05a994a6d034f65f8f0e6fdcaeab1a34d4a6b4b636e070a38bce737",
+            ),
+            (
+                &[0xaa; 131],
+                b"Test Using Larger Than Block-Size Key - Hash Key First",
+                "60e431591ee0b67f0d8a26aacbf5b77f8e0bc6213728c5140546040f0ee37f54",
+                "80b24263c7c1a3ebb71493c1dd7be8b49b46d1f41b4aeec1121b013783f8f3526b56d037e05f2598bd0fd2215d6a1e5295e64f73f63f0aec8b915a985d786598",
+            ),
+            (
+                &[0xaa; 131],
+                b"This is a test using a larger than block-size key and a larger than block-size data. The key needs to be hashed before being used by the HMAC algorithm.",
+                "9b09ffa71b942fcb27635fbcd5b0e944bfdc63644f0713938a7f51535c3a35e2",
+                "e37b6a775dc87dbaa4dfa9f96e5e3ffddebd71f8867289865df5a32d20cdc944b6022cac3c4982b10d5eeb55c3e4de15134676fb6de0446065c97440fa8c6a58",
+            ),
+        ];
+
+        for (key, data, sha256, sha512) in cases {
+            assert_eq!(vec_to_hex(&hmac_sha256(key, data)), sha256);
+            assert_eq!(vec_to_hex(&hmac_sha512(key, data)), sha512);
+        }
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let mut mac = HmacSha256::new(b"key");
+        mac.update(b"The quick brown fox ");
+        mac.update(b"jumps over the lazy dog");
+        assert_eq!(
+            vec_to_hex(&mac.finalize()),
+            "f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+    }
+
+    fn check_cached_midstate<H: Hasher>()
+    where
+        H::Output: PartialEq + std::fmt::Debug,
+    {
+        let key: Vec<u8> = (0..H::BLOCK_SIZE as u32 + 31).map(|i| i as u8).collect();
+        let keyed = Hmac::<H>::new(&key);
+
+        for message in [&b""[..], b"first message", &[0xaa; 200]] {
+            let mut reused = keyed.clone();
+            reused.update(message);
+
+            let mut fresh = Hmac::<H>::new(&key);
+            fresh.update(message);
+            assert_eq!(reused.finalize(), fresh.finalize());
+        }
+    }
+
+    #[test]
+    fn cloned_key_matches_fresh_key() {
+        check_cached_midstate::<Sha256>();
+        check_cached_midstate::<Sha512>();
+    }
 }
@@ -1,21 +1,78 @@
+pub mod sha1;
 pub mod sha256;
 pub mod sha512;
 pub mod hmac;
 pub mod pbkdf2;
 pub mod ripemd160;
+pub mod hkdf;
+pub mod tagged;
+pub mod newtypes;
+pub mod chacha20;
+pub mod poly1305;
+pub mod chacha20poly1305;
+pub mod blake2b;
+pub mod argon2;
+pub mod siphash;
+pub mod murmur3;
+pub mod batch;
+pub mod rfc6979;
 
-pub use sha256::sha256;
+pub use sha1::{Sha1, sha1};
+
+pub use sha256::{Sha256, sha256};
 pub use sha256::double_sha256;
 
-pub use sha512::sha512;
+pub use sha512::{Sha512, sha512};
 
-pub use hmac::hmac_sha512;
+pub use hmac::{Hmac, HmacSha256, HmacSha512, hmac, hmac_sha256, hmac_sha512};
 
-pub use pbkdf2::pbkdf2_hmac_sha512;
+pub use pbkdf2::{pbkdf2, pbkdf2_hmac_sha256, pbkdf2_hmac_sha512};
 
-pub use ripemd160::ripemd160;
+pub use hkdf::{hkdf, hkdf_extract, hkdf_expand, hkdf_sha256};
 
-pub fn hash160(input: &[u8]) -> Vec<u8> {
-    ripemd160(&sha256(input))
+pub use ripemd160::{Ripemd160, ripemd160};
+
+pub use tagged::{HashTag, tagged_engine, tagged_hash};
+
+pub use newtypes::{Txid, Wtxid, BlockHash, Hash256, Hash160};
+
+pub use chacha20::{chacha20_block, chacha20_xor};
+
+pub use poly1305::{Poly1305, poly1305};
+
+pub use chacha20poly1305::ChaCha20Poly1305;
+
+pub use blake2b::{Blake2b, blake2b};
+
+pub use argon2::{Argon2Params, argon2id, argon2id_keyed};
+
+pub use siphash::siphash24;
+
+pub use murmur3::murmur3_32;
+
+pub use batch::{sha256_many, double_sha256_many, ripemd160_many, hash160_many};
+
+pub use rfc6979::{HmacDrbg, generate_k, rfc6979_nonce, rfc6979_nonce_with_entropy, grind_entropy};
+
+pub trait Hasher: Clone {
+    const BLOCK_SIZE: usize;
+    const OUTPUT_SIZE: usize;
+
+    type Output: AsRef<[u8]> + AsMut<[u8]> + Copy;
+
+    fn new() -> Self;
+
+    fn update(&mut self, data: &[u8]);
+
+    fn finalize(self) -> Self::Output;
+
+    fn digest(data: &[u8]) -> Self::Output {
+        let mut hasher = Self::new();
+        hasher.update(data);
+        hasher.finalize()
+    }
 }
 
+pub fn hash160(input: &[u8]) -> [u8; 20] {
+    ripemd160(&sha256(input))
+}
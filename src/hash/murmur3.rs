@@ -0,0 +1,72 @@
+const C1: u32 = 0xcc9e2d51;
+const C2: u32 = 0x1b873593;
+
+pub fn murmur3_32(seed: u32, data: &[u8]) -> u32 {
+    let mut h = seed;
+
+    let mut blocks = data.chunks_exact(4);
+    for block in &mut blocks {
+        let mut k = u32::from_le_bytes([block[0], block[1], block[2], block[3]]);
+        k = k.wrapping_mul(C1);
+        k = k.rotate_left(15);
+        k = k.wrapping_mul(C2);
+
+        h ^= k;
+        h = h.rotate_left(13);
+        h = h.wrapping_mul(5).wrapping_add(0xe6546b64);
+    }
+
+    let tail = blocks.remainder();
+    let mut k = 0u32;
+    for (i, &byte) in tail.iter().enumerate() {
+        k ^= (byte as u32) << (8 * i);
+    }
+    if !tail.is_empty() {
+        k = k.wrapping_mul(C1);
+        k = k.rotate_left(15);
+        k = k.wrapping_mul(C2);
+        h ^= k;
+    }
+
+    h ^= data.len() as u32;
+    h ^= h >> 16;
+    h = h.wrapping_mul(0x85ebca6b);
+    h ^= h >> 13;
+    h = h.wrapping_mul(0xc2b2ae35);
+    h ^= h >> 16;
+    h
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::hex_to_vec;
+
+    // Bitcoin Core hash_tests MurmurHash3 vectors.
+    #[test]
+    fn murmur3_vectors() {
+        let cases = [
+            (0x00000000, "", 0x00000000),
+            (0xfba4c795, "", 0x6a396f08),
+            (0xffffffff, "", 0x81f16f39),
+            (0x00000000, "00", 0x514e28b7),
+            (0xfba4c795, "00", 0xea3f0b17),
+            (0x00000000, "ff", 0xfd6cf10d),
+            (0x00000000, "0011", 0x16c6b7ab),
+            (0x00000000, "001122", 0x8eb51c3d),
+            (0x00000000, "00112233", 0xb4471bf8),
+            (0x00000000, "0011223344", 0xe2301fa8),
+            (0x00000000, "001122334455", 0xfc2e4a15),
+            (0x00000000, "00112233445566", 0xb074502c),
+            (0x00000000, "0011223344556677", 0x8034d2a0),
+            (0x00000000, "001122334455667788", 0xb4698def),
+            (0x00000000, "21436587", 0xf55b516b),
+            (0x5082edee, "21436587", 0x2362f9de),
+            (0x00000000, "21", 0x72661cf4),
+            (0x5082edee, "21", 0x366cb0cb),
+        ];
+        for (seed, data, expected) in cases {
+            assert_eq!(murmur3_32(seed, &hex_to_vec(data).unwrap()), expected);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use std::fmt;
+
+use crate::hash::{double_sha256, hash160};
+use crate::utils::{FromHex, ToHex, HexError};
+
+macro_rules! hash_newtype {
+    ($name:ident, $len:expr, $reversed:expr) => {
+        #[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name([u8; $len]);
+
+        impl $name {
+            pub const fn from_byte_array(bytes: [u8; $len]) -> Self {
+                $name(bytes)
+            }
+
+            pub const fn to_byte_array(self) -> [u8; $len] {
+                self.0
+            }
+
+            pub const fn as_byte_array(&self) -> &[u8; $len] {
+                &self.0
+            }
+
+            pub const fn all_zeros() -> Self {
+                $name([0u8; $len])
+            }
+        }
+
+        impl AsRef<[u8]> for $name {
+            fn as_ref(&self) -> &[u8] {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                if $reversed {
+                    for byte in self.0.iter().rev() {
+                        write!(f, "{:02x}", byte)?;
+                    }
+                } else {
+                    for byte in self.0.iter() {
+                        write!(f, "{:02x}", byte)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+
+        impl ToHex for $name {
+            fn to_hex(&self) -> String {
+                self.to_string()
+            }
+        }
+
+        impl FromHex for $name {
+            type Error = HexError;
+
+            fn from_hex(hex: &str) -> Result<Self, HexError> {
+                let mut bytes = <[u8; $len]>::from_hex(hex)?;
+                if $reversed {
+                    bytes.reverse();
+                }
+                Ok($name(bytes))
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                write!(f, "{}({})", stringify!($name), self)
+            }
+        }
+    };
+}
+
+hash_newtype!(Txid, 32, true);
+hash_newtype!(Wtxid, 32, true);
+hash_newtype!(BlockHash, 32, true);
+hash_newtype!(Hash256, 32, true);
+hash_newtype!(Hash160, 20, false);
+
+impl Hash256 {
+    pub fn hash(data: &[u8]) -> Self {
+        Hash256(double_sha256(data))
+    }
+}
+
+impl Hash160 {
+    pub fn hash(data: &[u8]) -> Self {
+        Hash160(hash160(data))
+    }
+}
+
+impl From<Hash256> for Txid {
+    fn from(hash: Hash256) -> Self {
+        Txid(hash.0)
+    }
+}
+
+impl From<Hash256> for Wtxid {
+    fn from(hash: Hash256) -> Self {
+        Wtxid(hash.0)
+    }
+}
+
+impl From<Hash256> for BlockHash {
+    fn from(hash: Hash256) -> Self {
+        BlockHash(hash.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn txid_displays_reversed() {
+        let hex = "ae20ce9931fc9ed1c1e65e785510c2b58d75ac831fb92ab2925be4f295ff0883";
+        let txid = Txid::from_hex(hex).unwrap();
+        assert_eq!(txid.as_byte_array()[0], 0x83);
+        assert_eq!(txid.to_hex(), hex);
+        assert_eq!(format!("{:?}", txid), format!("Txid({})", hex));
+    }
+
+    #[test]
+    fn hash160_displays_in_order() {
+        let hex = "0102030405060708090a0b0c0d0e0f1011121314";
+        let hash = Hash160::from_hex(hex).unwrap();
+        assert_eq!(hash.as_byte_array()[0], 0x01);
+        assert_eq!(hash.to_string(), hex);
+        assert_eq!(Hash160::from_hex("0102"), Err(HexError::InvalidLength { expected: 20, found: 2 }));
+    }
+}
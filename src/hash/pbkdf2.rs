@@ -1,28 +1,85 @@
-use crate::hash::hmac_sha512;
+use crate::hash::{Hasher, Hmac, Sha256, Sha512};
+
+pub fn pbkdf2<H: Hasher>(password: &[u8], salt: &[u8], iterations: u32, dk_len: usize) -> Vec<u8> {
+    let prf = Hmac::<H>::new(password);
 
-pub fn pbkdf2_hmac_sha512(password: &[u8],  salt: &[u8], iterations: u32, dk_len: usize,) -> Vec<u8> {
     let mut dk = Vec::with_capacity(dk_len);
     let mut block_num: u32 = 1;
 
     while dk.len() < dk_len {
-        let mut salt_block = Vec::with_capacity(salt.len() + 4);
-        salt_block.extend_from_slice(salt);
-        salt_block.extend_from_slice(&block_num.to_be_bytes());
+        let mut mac = prf.clone();
+        mac.update(salt);
+        mac.update(&block_num.to_be_bytes());
 
-        let mut u = hmac_sha512(password, &salt_block);
-        let mut t = u.clone();
+        let mut u = mac.finalize();
+        let mut t = u;
 
         for _ in 1..iterations {
-            u = hmac_sha512(password, &u);
-            for (t_byte, u_byte) in t.iter_mut().zip(u.iter()) {
+            let mut mac = prf.clone();
+            mac.update(u.as_ref());
+            u = mac.finalize();
+            for (t_byte, u_byte) in t.as_mut().iter_mut().zip(u.as_ref().iter()) {
                 *t_byte ^= u_byte;
             }
         }
 
-        dk.extend_from_slice(&t);
+        dk.extend_from_slice(t.as_ref());
         block_num += 1;
     }
 
     dk.truncate(dk_len);
     dk
 }
+
+pub fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], iterations: u32, dk_len: usize) -> Vec<u8> {
+    pbkdf2::<Sha256>(password, salt, iterations, dk_len)
+}
+
+pub fn pbkdf2_hmac_sha512(password: &[u8], salt: &[u8], iterations: u32, dk_len: usize) -> Vec<u8> {
+    pbkdf2::<Sha512>(password, salt, iterations, dk_len)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::Sha1;
+    use crate::utils::vec_to_hex;
+
+    #[test]
+    fn rfc6070_vectors() {
+        assert_eq!(vec_to_hex(&pbkdf2::<Sha1>(b"password", b"salt", 1, 20)), "0c60c80f961f0e71f3a9b524af6012062fe037a6");
+        assert_eq!(vec_to_hex(&pbkdf2::<Sha1>(b"password", b"salt", 2, 20)), "ea6c014dc72d6f8ccd1ed92ace1d41f0d8de8957");
+        assert_eq!(vec_to_hex(&pbkdf2::<Sha1>(b"password", b"salt", 4096, 20)), "4b007901b765489abead49d926f721d065a429c1");
+        assert_eq!(
+            vec_to_hex(&pbkdf2::<Sha1>(b"passwordPASSWORDpassword", b"saltSALTsaltSALTsaltSALTsaltSALTsalt", 4096, 25)),
+            "3d2eec4fe41c849b80c8d83662c0e44a8b291a964cf2f07038"
+        );
+        assert_eq!(vec_to_hex(&pbkdf2::<Sha1>(b"pass\0word", b"sa\0lt", 4096, 16)), "56fa6aa75548099dcc37d7f03425e0c3");
+    }
+
+    #[test]
+    fn rfc7914_vectors() {
+        assert_eq!(
+            vec_to_hex(&pbkdf2_hmac_sha256(b"passwd", b"salt", 1, 64)),
+            "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc49ca9cccf179b645991664b39d77ef317c71b845b1e30bd509112041d3a19783"
+        );
+        assert_eq!(
+            vec_to_hex(&pbkdf2_hmac_sha256(b"Password", b"NaCl", 80000, 64)),
+            "4ddcd8f60b98be21830cee5ef22701f9641a4418d04c0414aeff08876b34ab56a1d425a1225833549adb841b51c9b3176a272bdebba1d078478f62b397f33c8d"
+        );
+    }
+
+    #[test]
+    fn bip39_seed() {
+        let seed = pbkdf2_hmac_sha512(
+            b"abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            b"mnemonicTREZOR",
+            2048,
+            64,
+        );
+        assert_eq!(
+            vec_to_hex(&seed),
+            "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+        );
+    }
+}
@@ -0,0 +1,184 @@
+const MASK: u32 = 0x3ffffff;
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    u32::from_le_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]])
+}
+
+#[derive(Clone)]
+pub struct Poly1305 {
+    r: [u32; 5],
+    h: [u32; 5],
+    pad: [u32; 4],
+    buffer: [u8; 16],
+    buffer_len: usize,
+}
+
+impl Poly1305 {
+    pub fn new(key: &[u8; 32]) -> Self {
+        let r = [
+            read_u32(key, 0) & 0x3ffffff,
+            (read_u32(key, 3) >> 2) & 0x3ffff03,
+            (read_u32(key, 6) >> 4) & 0x3ffc0ff,
+            (read_u32(key, 9) >> 6) & 0x3f03fff,
+            (read_u32(key, 12) >> 8) & 0x00fffff,
+        ];
+        let pad = [
+            read_u32(key, 16),
+            read_u32(key, 20),
+            read_u32(key, 24),
+            read_u32(key, 28),
+        ];
+
+        Poly1305 {
+            r,
+            h: [0u32; 5],
+            pad,
+            buffer: [0u8; 16],
+            buffer_len: 0,
+        }
+    }
+
+    fn process_block(&mut self, block: &[u8], hibit: u32) {
+        let [r0, r1, r2, r3, r4] = self.r.map(|x| x as u64);
+        let (s1, s2, s3, s4) = (r1 * 5, r2 * 5, r3 * 5, r4 * 5);
+
+        let h0 = (self.h[0] + (read_u32(block, 0) & MASK)) as u64;
+        let h1 = (self.h[1] + ((read_u32(block, 3) >> 2) & MASK)) as u64;
+        let h2 = (self.h[2] + ((read_u32(block, 6) >> 4) & MASK)) as u64;
+        let h3 = (self.h[3] + ((read_u32(block, 9) >> 6) & MASK)) as u64;
+        let h4 = (self.h[4] + ((read_u32(block, 12) >> 8) | hibit)) as u64;
+
+        let d0 = h0 * r0 + h1 * s4 + h2 * s3 + h3 * s2 + h4 * s1;
+        let mut d1 = h0 * r1 + h1 * r0 + h2 * s4 + h3 * s3 + h4 * s2;
+        let mut d2 = h0 * r2 + h1 * r1 + h2 * r0 + h3 * s4 + h4 * s3;
+        let mut d3 = h0 * r3 + h1 * r2 + h2 * r1 + h3 * r0 + h4 * s4;
+        let mut d4 = h0 * r4 + h1 * r3 + h2 * r2 + h3 * r1 + h4 * r0;
+
+        let mut c = d0 >> 26;
+        self.h[0] = (d0 as u32) & MASK;
+        d1 += c;
+        c = d1 >> 26;
+        self.h[1] = (d1 as u32) & MASK;
+        d2 += c;
+        c = d2 >> 26;
+        self.h[2] = (d2 as u32) & MASK;
+        d3 += c;
+        c = d3 >> 26;
+        self.h[3] = (d3 as u32) & MASK;
+        d4 += c;
+        c = d4 >> 26;
+        self.h[4] = (d4 as u32) & MASK;
+
+        self.h[0] += (c as u32) * 5;
+        let c = self.h[0] >> 26;
+        self.h[0] &= MASK;
+        self.h[1] += c;
+    }
+
+    pub fn update(&mut self, mut data: &[u8]) {
+        if self.buffer_len > 0 {
+            let take = (16 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len < 16 {
+                return;
+            }
+            let block = self.buffer;
+            self.process_block(&block, 1 << 24);
+            self.buffer_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(16);
+        for block in &mut blocks {
+            self.process_block(block, 1 << 24);
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    pub fn finalize(mut self) -> [u8; 16] {
+        if self.buffer_len > 0 {
+            let mut block = [0u8; 16];
+            block[..self.buffer_len].copy_from_slice(&self.buffer[..self.buffer_len]);
+            block[self.buffer_len] = 1;
+            self.process_block(&block, 0);
+        }
+
+        let mut h = self.h;
+
+        let mut c = h[1] >> 26;
+        h[1] &= MASK;
+        for limb in h.iter_mut().skip(2) {
+            *limb += c;
+            c = *limb >> 26;
+            *limb &= MASK;
+        }
+        h[0] += c * 5;
+        c = h[0] >> 26;
+        h[0] &= MASK;
+        h[1] += c;
+
+        let mut g = [0u32; 5];
+        g[0] = h[0] + 5;
+        c = g[0] >> 26;
+        g[0] &= MASK;
+        for i in 1..4 {
+            g[i] = h[i] + c;
+            c = g[i] >> 26;
+            g[i] &= MASK;
+        }
+        g[4] = h[4].wrapping_add(c).wrapping_sub(1 << 26);
+
+        let select_g = (g[4] >> 31).wrapping_sub(1);
+        for i in 0..5 {
+            h[i] = (h[i] & !select_g) | (g[i] & select_g);
+        }
+
+        let words = [
+            h[0] | (h[1] << 26),
+            (h[1] >> 6) | (h[2] << 20),
+            (h[2] >> 12) | (h[3] << 14),
+            (h[3] >> 18) | (h[4] << 8),
+        ];
+
+        let mut tag = [0u8; 16];
+        let mut carry = 0u64;
+        for i in 0..4 {
+            let f = words[i] as u64 + self.pad[i] as u64 + carry;
+            tag[i * 4..i * 4 + 4].copy_from_slice(&(f as u32).to_le_bytes());
+            carry = f >> 32;
+        }
+        tag
+    }
+}
+
+pub fn poly1305(key: &[u8; 32], message: &[u8]) -> [u8; 16] {
+    let mut mac = Poly1305::new(key);
+    mac.update(message);
+    mac.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    // RFC8439 section 2.5.2.
+    #[test]
+    fn rfc8439_vector() {
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&hex_to_vec("85d6be7857556d337f4452fe42d506a80103808afb0db2fd4abff6af4149f51b").unwrap());
+
+        let tag = poly1305(&key, b"Cryptographic Forum Research Group");
+        assert_eq!(vec_to_hex(&tag), "a8061dc1305136c6c22b8baf0c0127a9");
+
+        let mut mac = Poly1305::new(&key);
+        mac.update(b"Cryptographic ");
+        mac.update(b"Forum Research Group");
+        assert_eq!(mac.finalize(), tag);
+    }
+}
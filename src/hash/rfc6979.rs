@@ -0,0 +1,270 @@
+use crate::entropy::EntropySource;
+use crate::hash::{Hasher, Hmac, Sha256};
+use crate::error::Error;
+
+pub const SECP256K1_ORDER: [u8; 32] = [
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff,
+    0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xfe,
+    0xba, 0xae, 0xdc, 0xe6, 0xaf, 0x48, 0xa0, 0x3b,
+    0xbf, 0xd2, 0x5e, 0x8c, 0xd0, 0x36, 0x41, 0x41,
+];
+
+#[derive(Clone)]
+pub struct HmacDrbg<H: Hasher> {
+    k: H::Output,
+    v: H::Output,
+    retry: bool,
+}
+
+impl<H: Hasher> HmacDrbg<H> {
+    pub fn new(seed: &[&[u8]]) -> Self {
+        let mut k = H::digest(&[]);
+        let mut v = k;
+        k.as_mut().fill(0x00);
+        v.as_mut().fill(0x01);
+
+        let mut drbg = HmacDrbg { k, v, retry: false };
+        drbg.update(seed);
+        drbg
+    }
+
+    fn hmac_v(&self, suffix: Option<u8>, data: &[&[u8]]) -> H::Output {
+        let mut mac = Hmac::<H>::new(self.k.as_ref());
+        mac.update(self.v.as_ref());
+        if let Some(byte) = suffix {
+            mac.update(&[byte]);
+        }
+        for part in data {
+            mac.update(part);
+        }
+        mac.finalize()
+    }
+
+    fn update(&mut self, data: &[&[u8]]) {
+        self.k = self.hmac_v(Some(0x00), data);
+        self.v = self.hmac_v(None, &[]);
+
+        if data.iter().all(|part| part.is_empty()) {
+            return;
+        }
+
+        self.k = self.hmac_v(Some(0x01), data);
+        self.v = self.hmac_v(None, &[]);
+    }
+
+    pub fn generate(&mut self, output: &mut [u8]) {
+        if self.retry {
+            self.update(&[]);
+        }
+
+        let mut written = 0;
+        while written < output.len() {
+            self.v = self.hmac_v(None, &[]);
+            let take = (output.len() - written).min(H::OUTPUT_SIZE);
+            output[written..written + take].copy_from_slice(&self.v.as_ref()[..take]);
+            written += take;
+        }
+
+        self.retry = true;
+    }
+}
+
+fn bit_length(bytes: &[u8]) -> usize {
+    match bytes.iter().position(|&b| b != 0) {
+        Some(i) => (bytes.len() - i) * 8 - bytes[i].leading_zeros() as usize,
+        None => 0,
+    }
+}
+
+fn int2octets(bytes: &[u8], rlen: usize) -> Vec<u8> {
+    let mut value = vec![0u8; rlen];
+    if bytes.len() > rlen {
+        value.copy_from_slice(&bytes[bytes.len() - rlen..]);
+    } else {
+        value[rlen - bytes.len()..].copy_from_slice(bytes);
+    }
+    value
+}
+
+fn bits2int(bytes: &[u8], qlen: usize) -> Vec<u8> {
+    let rlen = qlen.div_ceil(8);
+    let blen = bytes.len() * 8;
+
+    let mut value = bytes.to_vec();
+    if blen > qlen {
+        let shift = blen - qlen;
+        let byte_shift = shift / 8;
+        let bit_shift = shift % 8;
+
+        value.truncate(value.len() - byte_shift);
+        if bit_shift > 0 {
+            let mut carry = 0u8;
+            for byte in value.iter_mut() {
+                let next_carry = *byte << (8 - bit_shift);
+                *byte = (*byte >> bit_shift) | carry;
+                carry = next_carry;
+            }
+        }
+    }
+
+    int2octets(&value, rlen)
+}
+
+fn is_valid_scalar(k: &[u8], q: &[u8]) -> bool {
+    k.iter().any(|&b| b != 0) && k < q
+}
+
+fn sub_assign(a: &mut [u8], b: &[u8]) {
+    let mut borrow = 0i16;
+    for (x, y) in a.iter_mut().zip(b.iter()).rev() {
+        let diff = *x as i16 - *y as i16 - borrow;
+        borrow = if diff < 0 { 1 } else { 0 };
+        *x = diff.rem_euclid(256) as u8;
+    }
+}
+
+fn bits2octets(bytes: &[u8], q: &[u8], qlen: usize) -> Vec<u8> {
+    let mut z = bits2int(bytes, qlen);
+    if z.as_slice() >= q {
+        sub_assign(&mut z, q);
+    }
+    z
+}
+
+pub fn generate_k<H: Hasher>(q: &[u8], private_key: &[u8], message_hash: &[u8], extra_entropy: &[u8]) -> Result<Vec<u8>, Error> {
+    let qlen = bit_length(q);
+    if qlen < 2 {
+        return Err(Error::Hash("RFC6979 group order must be at least 2"));
+    }
+    let rlen = qlen.div_ceil(8);
+    let q = int2octets(q, rlen);
+
+    let excess = private_key.len().saturating_sub(rlen);
+    let x = int2octets(private_key, rlen);
+    if private_key[..excess].iter().any(|&b| b != 0) || !is_valid_scalar(&x, &q) {
+        return Err(Error::Hash("RFC6979 private key out of range"));
+    }
+    let h = bits2octets(message_hash, &q, qlen);
+
+    let mut drbg = HmacDrbg::<H>::new(&[&x, &h, extra_entropy]);
+    let mut t = vec![0u8; rlen];
+    loop {
+        drbg.generate(&mut t);
+        let k = bits2int(&t, qlen);
+        if is_valid_scalar(&k, &q) {
+            return Ok(k);
+        }
+    }
+}
+
+pub fn rfc6979_nonce(secret_key: &[u8; 32], message_hash: &[u8; 32], extra_entropy: Option<&[u8; 32]>) -> Result<[u8; 32], Error> {
+    let extra: &[u8] = match extra_entropy {
+        Some(data) => data,
+        None => &[],
+    };
+
+    let k = generate_k::<Sha256>(&SECP256K1_ORDER, secret_key, message_hash, extra)?;
+
+    let mut nonce = [0u8; 32];
+    nonce.copy_from_slice(&k);
+    Ok(nonce)
+}
+
+pub fn grind_entropy(counter: u32) -> [u8; 32] {
+    let mut entropy = [0u8; 32];
+    entropy[..4].copy_from_slice(&counter.to_le_bytes());
+    entropy
+}
+
+pub fn rfc6979_nonce_with_entropy<E: EntropySource + ?Sized>(
+    secret_key: &[u8; 32],
+    message_hash: &[u8; 32],
+    source: &mut E,
+) -> Result<[u8; 32], Error> {
+    let mut entropy = [0u8; 32];
+    source.fill_bytes(&mut entropy)?;
+    rfc6979_nonce(secret_key, message_hash, Some(&entropy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::hash::{sha256, sha512, Sha512};
+    use crate::utils::{hex_to_vec, vec_to_hex};
+    use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+    // RFC6979 appendix A.2.5 (P-256).
+    #[test]
+    fn rfc6979_p256_vectors() {
+        let q = hex_to_vec("ffffffff00000000ffffffffffffffffbce6faada7179e84f3b9cac2fc632551").unwrap();
+        let x = hex_to_vec("c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721").unwrap();
+
+        assert_eq!(
+            vec_to_hex(&generate_k::<Sha256>(&q, &x, &sha256(b"sample"), &[]).unwrap()),
+            "a6e3c57dd01abe90086538398355dd4c3b17aa873382b0f24d6129493d8aad60"
+        );
+        assert_eq!(
+            vec_to_hex(&generate_k::<Sha512>(&q, &x, &sha512(b"sample"), &[]).unwrap()),
+            "5fa81c63109badb88c1f367b47da606da28cad69aa22c4fe6ad7df73a7173aa5"
+        );
+        assert_eq!(
+            vec_to_hex(&generate_k::<Sha256>(&q, &x, &sha256(b"test"), &[]).unwrap()),
+            "d16b6ae827f17175e040871a1c7ec3500192c4c92677336ec2537acaee0008e0"
+        );
+    }
+
+    // RFC6979 appendix A.1.2 (K-163), where qlen is not a multiple of 8.
+    #[test]
+    fn rfc6979_k163_vector() {
+        let q = hex_to_vec("04000000000000000000020108a2e0cc0d99f8a5ef").unwrap();
+        let x = hex_to_vec("009a4d6792295a7f730fc3f2b49cbc0f62e862272f").unwrap();
+        assert_eq!(
+            vec_to_hex(&generate_k::<Sha256>(&q, &x, &sha256(b"sample"), &[]).unwrap()),
+            "023af4074c90a02b3fe61d286d5c87f425e6bdd81b"
+        );
+    }
+
+    #[test]
+    fn secp256k1_vector() {
+        let mut secret_key = [0u8; 32];
+        secret_key[31] = 1;
+        let nonce = rfc6979_nonce(&secret_key, &sha256(b"Satoshi Nakamoto"), None).unwrap();
+        assert_eq!(vec_to_hex(&nonce), "8f8a276c19f4149656b280621e358cce24f5f52542772691ee69063b74f15d15");
+    }
+
+    #[test]
+    fn matches_libsecp256k1() {
+        let secp = Secp256k1::new();
+        for i in 1..8u8 {
+            let secret_key = [i; 32];
+            let sk = SecretKey::from_slice(&secret_key).unwrap();
+            let message_hash = sha256(&[i]);
+            let msg = Message::from_digest(message_hash);
+
+            let sig = secp.sign_ecdsa(&msg, &sk).serialize_compact();
+            let k = rfc6979_nonce(&secret_key, &message_hash, None).unwrap();
+            let r = PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&k).unwrap()).serialize();
+            assert_eq!(r[1..], sig[..32]);
+
+            let entropy = grind_entropy(i as u32);
+            let sig = secp.sign_ecdsa_with_noncedata(&msg, &sk, &entropy).serialize_compact();
+            let k = rfc6979_nonce(&secret_key, &message_hash, Some(&entropy)).unwrap();
+            let r = PublicKey::from_secret_key(&secp, &SecretKey::from_slice(&k).unwrap()).serialize();
+            assert_eq!(r[1..], sig[..32]);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_inputs() {
+        let hash = sha256(b"sample");
+        assert!(generate_k::<Sha256>(&[], &[1], &hash, &[]).is_err());
+        assert!(generate_k::<Sha256>(&[0x01], &[1], &hash, &[]).is_err());
+        assert!(generate_k::<Sha256>(&[0x02], &[1], &hash, &[]).is_ok());
+        assert!(generate_k::<Sha256>(&[0x07], &[0x07], &hash, &[]).is_err());
+        assert!(generate_k::<Sha256>(&[0x07], &[0x01, 0x01], &hash, &[]).is_err());
+
+        assert!(rfc6979_nonce(&[0u8; 32], &hash, None).is_err());
+        assert!(rfc6979_nonce(&SECP256K1_ORDER, &hash, None).is_err());
+        assert!(rfc6979_nonce(&[0xff; 32], &hash, None).is_err());
+    }
+}
@@ -1,153 +1,254 @@
-pub fn ripemd160(input: &[u8]) -> Vec<u8> {
-    let mut state = [
-        0x67452301u32,
-        0xefcdab89u32,
-        0x98badcfeu32,
-        0x10325476u32,
-        0xc3d2e1f0u32,
-    ];
-
-    let mut data = input.to_vec();
-    let bit_len = (data.len() as u64) * 8;
-
-    data.push(0x80);
-    while data.len() % 64 != 56 {
-        data.push(0);
+use crate::hash::Hasher;
+
+pub(crate) const H: [u32; 5] = [
+    0x67452301,
+    0xefcdab89,
+    0x98badcfe,
+    0x10325476,
+    0xc3d2e1f0,
+];
+
+pub(crate) const R: [usize; 80] = [
+     0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15,
+     7,  4, 13,  1, 10,  6, 15,  3, 12,  0,  9,  5,  2, 14, 11,  8,
+     3, 10, 14,  4,  9, 15,  8,  1,  2,  7,  0,  6, 13, 11,  5, 12,
+     1,  9, 11, 10,  0,  8, 12,  4, 13,  3,  7, 15, 14,  5,  6,  2,
+     4,  0,  5,  9,  7, 12,  2, 10, 14,  1,  3,  8, 11,  6, 15, 13,
+];
+pub(crate) const S: [u32; 80] = [
+    11, 14, 15, 12,  5,  8,  7,  9, 11, 13, 14, 15,  6,  7,  9,  8,
+     7,  6,  8, 13, 11,  9,  7, 15,  7, 12, 15,  9, 11,  7, 13, 12,
+    11, 13,  6,  7, 14,  9, 13, 15, 14,  8, 13,  6,  5, 12,  7,  5,
+    11, 12, 14, 15, 14, 15,  9,  8,  9, 14,  5,  6,  8,  6,  5, 12,
+     9, 15,  5, 11,  6,  8, 13, 12,  5, 12, 13, 14, 11,  8,  5,  6,
+];
+
+pub(crate) const R_PRIME: [usize; 80] = [
+     5, 14,  7,  0,  9,  2, 11,  4, 13,  6, 15,  8,  1, 10,  3, 12,
+     6, 11,  3,  7,  0, 13,  5, 10, 14, 15,  8, 12,  4,  9,  1,  2,
+    15,  5,  1,  3,  7, 14,  6,  9, 11,  8, 12,  2, 10,  0,  4, 13,
+     8,  6,  4,  1,  3, 11, 15,  0,  5, 12,  2, 13,  9,  7, 10, 14,
+    12, 15, 10,  4,  1,  5,  8,  7,  6,  2, 13, 14,  0,  3,  9, 11,
+];
+pub(crate) const S_PRIME: [u32; 80] = [
+     8,  9,  9, 11, 13, 15, 15,  5,  7,  7,  8, 11, 14, 14, 12,  6,
+     9, 13, 15,  7, 12,  8,  9, 11,  7,  7, 12,  7,  6, 15, 13, 11,
+     9,  7, 15, 11,  8,  6,  6, 14, 12, 13,  5, 14, 13, 13,  7,  5,
+    15,  5,  8, 11, 14, 14,  6, 14,  6,  9, 12,  9, 12,  5, 15,  8,
+     8,  5, 12,  9, 12,  5, 14,  6,  8, 13,  6,  5, 15, 13, 11, 11,
+];
+
+pub(crate) const K: [u32; 5] = [
+    0x00000000,
+    0x5A827999,
+    0x6ED9EBA1,
+    0x8F1BBCDC,
+    0xA953FD4E,
+];
+pub(crate) const K_PRIME: [u32; 5] = [
+    0x50A28BE6,
+    0x5C4DD124,
+    0x6D703EF3,
+    0x7A6D76E9,
+    0x00000000,
+];
+
+fn compress_block(block: &[u8], state: &mut [u32; 5]) {
+    let mut x = [0u32; 16];
+    for (i, word) in x.iter_mut().enumerate() {
+        let j = i * 4;
+        *word = u32::from_le_bytes([block[j], block[j + 1], block[j + 2], block[j + 3]]);
     }
-    data.extend(&bit_len.to_le_bytes());
-
-    const R: [usize; 80] = [
-         0,  1,  2,  3,  4,  5,  6,  7,  8,  9, 10, 11, 12, 13, 14, 15,
-         7,  4, 13,  1, 10,  6, 15,  3, 12,  0,  9,  5,  2, 14, 11,  8,
-         3, 10, 14,  4,  9, 15,  8,  1,  2,  7,  0,  6, 13, 11,  5, 12,
-         1,  9, 11, 10,  0,  8, 12,  4, 13,  3,  7, 15, 14,  5,  6,  2,
-         4,  0,  5,  9,  7, 12,  2, 10, 14,  1,  3,  8, 11,  6, 15, 13,
-    ];
-    const S: [u32; 80] = [
-        11, 14, 15, 12,  5,  8,  7,  9, 11, 13, 14, 15,  6,  7,  9,  8,
-         7,  6,  8, 13, 11,  9,  7, 15,  7, 12, 15,  9, 11,  7, 13, 12,
-        11, 13,  6,  7, 14,  9, 13, 15, 14,  8, 13,  6,  5, 12,  7,  5,
-        11, 12, 14, 15, 14, 15,  9,  8,  9, 14,  5,  6,  8,  6,  5, 12,
-         9, 15,  5, 11,  6,  8, 13, 12,  5, 12, 13, 14, 11,  8,  5,  6,
-    ];
-
-    const R_PRIME: [usize; 80] = [
-         5, 14,  7,  0,  9,  2, 11,  4, 13,  6, 15,  8,  1, 10,  3, 12,
-         6, 11,  3,  7,  0, 13,  5, 10, 14, 15,  8, 12,  4,  9,  1,  2,
-        15,  5,  1,  3,  7, 14,  6,  9, 11,  8, 12,  2, 10,  0,  4, 13,
-         8,  6,  4,  1,  3, 11, 15,  0,  5, 12,  2, 13,  9,  7, 10, 14,
-        12, 15, 10,  4,  1,  5,  8,  7,  6,  2, 13, 14,  0,  3,  9, 11,
-    ];
-    const S_PRIME: [u32; 80] = [
-         8,  9,  9, 11, 13, 15, 15,  5,  7,  7,  8, 11, 14, 14, 12,  6,
-         9, 13, 15,  7, 12,  8,  9, 11,  7,  7, 12,  7,  6, 15, 13, 11,
-         9,  7, 15, 11,  8,  6,  6, 14, 12, 13,  5, 14, 13, 13,  7,  5,
-        15,  5,  8, 11, 14, 14,  6, 14,  6,  9, 12,  9, 12,  5, 15,  8,
-         8,  5, 12,  9, 12,  5, 14,  6,  8, 13,  6,  5, 15, 13, 11, 11,
-    ];
-
-    const K: [u32; 5] = [
-        0x00000000,
-        0x5A827999,
-        0x6ED9EBA1,
-        0x8F1BBCDC,
-        0xA953FD4E,
-    ];
-    const K_PRIME: [u32; 5] = [
-        0x50A28BE6,
-        0x5C4DD124,
-        0x6D703EF3,
-        0x7A6D76E9,
-        0x00000000,
-    ];
-
-    for block in data.chunks(64) {
-        let mut x = [0u32; 16];
-        for i in 0..16 {
-            let j = i * 4;
-            x[i] = u32::from_le_bytes([block[j], block[j + 1], block[j + 2], block[j + 3]]);
+
+    let mut a  = state[0];
+    let mut b  = state[1];
+    let mut c  = state[2];
+    let mut d  = state[3];
+    let mut e  = state[4];
+
+    let mut a_prime  = state[0];
+    let mut b_prime  = state[1];
+    let mut c_prime  = state[2];
+    let mut d_prime  = state[3];
+    let mut e_prime  = state[4];
+
+    let f = |j: usize, x: u32, y: u32, z: u32| -> u32 {
+        match j / 16 {
+            0 => x ^ y ^ z,
+            1 => (x & y) | (!x & z),
+            2 => (x | !y) ^ z,
+            3 => (x & z) | (y & !z),
+            4 => x ^ (y | !z),
+            _ => unreachable!(),
+        }
+    };
+    let f_prime = |j: usize, x: u32, y: u32, z: u32| -> u32 {
+        match j / 16 {
+            0 => x ^ (y | !z),
+            1 => (x & z) | (y & !z),
+            2 => (x | !y) ^ z,
+            3 => (x & y) | (!x & z),
+            4 => x ^ y ^ z,
+            _ => unreachable!(),
         }
+    };
 
-        let mut a  = state[0];
-        let mut b  = state[1];
-        let mut c  = state[2];
-        let mut d  = state[3];
-        let mut e  = state[4];
-
-        let mut a_prime  = state[0];
-        let mut b_prime  = state[1];
-        let mut c_prime  = state[2];
-        let mut d_prime  = state[3];
-        let mut e_prime  = state[4];
-
-        let f = |j: usize, x: u32, y: u32, z: u32| -> u32 {
-            match j / 16 {
-                0 => x ^ y ^ z,
-                1 => (x & y) | (!x & z),
-                2 => (x | !y) ^ z,
-                3 => (x & z) | (y & !z),
-                4 => x ^ (y | !z),
-                _ => unreachable!(),
-            }
-        };
-        let f_prime = |j: usize, x: u32, y: u32, z: u32| -> u32 {
-            match j / 16 {
-                0 => x ^ (y | !z),
-                1 => (x & z) | (y & !z),
-                2 => (x | !y) ^ z,
-                3 => (x & y) | (!x & z),
-                4 => x ^ y ^ z,
-                _ => unreachable!(),
+    for j in 0..80 {
+        let temp = a
+            .wrapping_add(f(j, b, c, d))
+            .wrapping_add(x[R[j]])
+            .wrapping_add(K[j / 16])
+            .rotate_left(S[j])
+            .wrapping_add(e);
+        a = e;
+        e = d;
+        d = c.rotate_left(10);
+        c = b;
+        b = temp;
+
+        let temp_prime = a_prime
+            .wrapping_add(f_prime(j, b_prime, c_prime, d_prime))
+            .wrapping_add(x[R_PRIME[j]])
+            .wrapping_add(K_PRIME[j / 16])
+            .rotate_left(S_PRIME[j])
+            .wrapping_add(e_prime);
+        a_prime = e_prime;
+        e_prime = d_prime;
+        d_prime = c_prime.rotate_left(10);
+        c_prime = b_prime;
+        b_prime = temp_prime;
+    }
+
+    let t = state[1]
+        .wrapping_add(c)
+        .wrapping_add(d_prime);
+    state[1] = state[2]
+        .wrapping_add(d)
+        .wrapping_add(e_prime);
+    state[2] = state[3]
+        .wrapping_add(e)
+        .wrapping_add(a_prime);
+    state[3] = state[4]
+        .wrapping_add(a)
+        .wrapping_add(b_prime);
+    state[4] = state[0]
+        .wrapping_add(b)
+        .wrapping_add(c_prime);
+    state[0] = t;
+}
+
+#[derive(Clone)]
+pub struct Ripemd160 {
+    state: [u32; 5],
+    buffer: [u8; 64],
+    buffer_len: usize,
+    length: u64,
+}
+
+impl Hasher for Ripemd160 {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 20;
+
+    type Output = [u8; 20];
+
+    fn new() -> Self {
+        Ripemd160 {
+            state: H,
+            buffer: [0u8; 64],
+            buffer_len: 0,
+            length: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        if self.buffer_len > 0 {
+            let take = (64 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len < 64 {
+                return;
             }
-        };
-
-        for j in 0..80 {
-            let temp = a
-                .wrapping_add(f(j, b, c, d))
-                .wrapping_add(x[R[j]])
-                .wrapping_add(K[j / 16])
-                .rotate_left(S[j])
-                .wrapping_add(e);
-            a = e;
-            e = d;
-            d = c.rotate_left(10);
-            c = b;
-            b = temp;
-
-            let temp_prime = a_prime
-                .wrapping_add(f_prime(j, b_prime, c_prime, d_prime))
-                .wrapping_add(x[R_PRIME[j]])
-                .wrapping_add(K_PRIME[j / 16])
-                .rotate_left(S_PRIME[j])
-                .wrapping_add(e_prime);
-            a_prime = e_prime;
-            e_prime = d_prime;
-            d_prime = c_prime.rotate_left(10);
-            c_prime = b_prime;
-            b_prime = temp_prime;
+            compress_block(&self.buffer, &mut self.state);
+            self.buffer_len = 0;
         }
 
-        let t = state[1]
-            .wrapping_add(c)
-            .wrapping_add(d_prime);
-        state[1] = state[2]
-            .wrapping_add(d)
-            .wrapping_add(e_prime);
-        state[2] = state[3]
-            .wrapping_add(e)
-            .wrapping_add(a_prime);
-        state[3] = state[4]
-            .wrapping_add(a)
-            .wrapping_add(b_prime);
-        state[4] = state[0]
-            .wrapping_add(b)
-            .wrapping_add(c_prime);
-        state[0] = t;
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            compress_block(block, &mut self.state);
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    fn finalize(mut self) -> [u8; 20] {
+        let message_len_bits = self.length * 8;
+
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+        if self.buffer_len >= 56 {
+            compress_block(&self.buffer, &mut self.state);
+            self.buffer = [0u8; 64];
+        }
+        self.buffer[56..].copy_from_slice(&message_len_bits.to_le_bytes());
+        compress_block(&self.buffer, &mut self.state);
+
+        let mut digest = [0u8; 20];
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+        digest
+    }
+}
+
+pub fn ripemd160(input: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // Test vectors from the RIPEMD-160 reference page.
+    #[test]
+    fn ripemd160_vectors() {
+        let cases: [(&[u8], &str); 5] = [
+            (b"", "9c1185a5c5e9fc54612808977ee8f548b2258d31"),
+            (b"a", "0bdc9d2d256b3ee9daae347be6f4dc835a467ffe"),
+            (b"abc", "8eb208f7e05d987a9b044a8e98c6b087f15a0bfc"),
+            (b"message digest", "5d0689ef49d2fae572b881b123a85ffa21595f36"),
+            (b"abcdefghijklmnopqrstuvwxyz", "f71c27109c692c1b56bbdceb5b9d2865b3708dbc"),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(vec_to_hex(&ripemd160(message)), expected);
+        }
+        assert_eq!(vec_to_hex(&ripemd160(&vec![b'a'; 1_000_000])), "52783243c1697bdbe16d37f97f68f08325dc1528");
     }
 
-    let mut digest = Vec::with_capacity(20);
-    for &word in &state {
-        digest.extend_from_slice(&word.to_le_bytes());
+    #[test]
+    fn split_updates_match_one_shot() {
+        let message: Vec<u8> = (0..300u32).map(|i| (i * 7 + 3) as u8).collect();
+        for len in [0, 1, 55, 56, 63, 64, 111, 112, 128, 300] {
+            let one_shot = Ripemd160::digest(&message[..len]);
+            for split in [0, 1, 55, 56, 63, 64, 111, 112, 128] {
+                let split = split.min(len);
+                let mut hasher = Ripemd160::new();
+                hasher.update(&message[..split]);
+                hasher.update(&message[split..len]);
+                assert_eq!(hasher.finalize(), one_shot);
+            }
+
+            let mut hasher = Ripemd160::new();
+            for byte in &message[..len] {
+                hasher.update(std::slice::from_ref(byte));
+            }
+            assert_eq!(hasher.finalize(), one_shot);
+        }
     }
-    digest
-}
\ No newline at end of file
+}
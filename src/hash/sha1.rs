@@ -0,0 +1,172 @@
+use crate::hash::Hasher;
+
+const H: [u32; 5] = [
+    0x67452301,
+    0xefcdab89,
+    0x98badcfe,
+    0x10325476,
+    0xc3d2e1f0,
+];
+
+const K: [u32; 4] = [
+    0x5a827999,
+    0x6ed9eba1,
+    0x8f1bbcdc,
+    0xca62c1d6,
+];
+
+fn expand_block(block: &[u8]) -> [u32; 80] {
+    let mut w = [0u32; 80];
+
+    for i in 0..16 {
+        w[i] = u32::from_be_bytes([
+            block[i * 4],
+            block[i * 4 + 1],
+            block[i * 4 + 2],
+            block[i * 4 + 3],
+        ]);
+    }
+
+    for i in 16..80 {
+        w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+    }
+
+    w
+}
+
+fn compress_block(w: &[u32; 80], h: &mut [u32; 5]) {
+    let mut a = h[0];
+    let mut b = h[1];
+    let mut c = h[2];
+    let mut d = h[3];
+    let mut e = h[4];
+
+    for i in 0..80 {
+        let f = match i / 20 {
+            0 => (b & c) | ((!b) & d),
+            1 => b ^ c ^ d,
+            2 => (b & c) | (b & d) | (c & d),
+            _ => b ^ c ^ d,
+        };
+
+        let temp = a.rotate_left(5)
+            .wrapping_add(f)
+            .wrapping_add(e)
+            .wrapping_add(K[i / 20])
+            .wrapping_add(w[i]);
+
+        e = d;
+        d = c;
+        c = b.rotate_left(30);
+        b = a;
+        a = temp;
+    }
+
+    h[0] = h[0].wrapping_add(a);
+    h[1] = h[1].wrapping_add(b);
+    h[2] = h[2].wrapping_add(c);
+    h[3] = h[3].wrapping_add(d);
+    h[4] = h[4].wrapping_add(e);
+}
+
+#[derive(Clone)]
+pub struct Sha1 {
+    state: [u32; 5],
+    buffer: [u8; 64],
+    buffer_len: usize,
+    length: u64,
+}
+
+impl Hasher for Sha1 {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 20;
+
+    type Output = [u8; 20];
+
+    fn new() -> Self {
+        Sha1 {
+            state: H,
+            buffer: [0u8; 64],
+            buffer_len: 0,
+            length: 0,
+        }
+    }
+
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        if self.buffer_len > 0 {
+            let take = (64 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len < 64 {
+                return;
+            }
+            compress_block(&expand_block(&self.buffer), &mut self.state);
+            self.buffer_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            compress_block(&expand_block(block), &mut self.state);
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    fn finalize(mut self) -> [u8; 20] {
+        let message_len_bits = self.length * 8;
+
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+        if self.buffer_len >= 56 {
+            compress_block(&expand_block(&self.buffer), &mut self.state);
+            self.buffer = [0u8; 64];
+        }
+        self.buffer[56..].copy_from_slice(&message_len_bits.to_be_bytes());
+        compress_block(&expand_block(&self.buffer), &mut self.state);
+
+        let mut digest = [0u8; 20];
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+pub fn sha1(message: &[u8]) -> [u8; 20] {
+    Sha1::digest(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // FIPS 180 example messages.
+    #[test]
+    fn fips180_vectors() {
+        assert_eq!(vec_to_hex(&sha1(b"abc")), "a9993e364706816aba3e25717850c26c9cd0d89d");
+        assert_eq!(vec_to_hex(&sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(
+            vec_to_hex(&sha1(b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq")),
+            "84983e441c3bd26ebaae4aa1f95129e5e54670f1"
+        );
+        assert_eq!(vec_to_hex(&sha1(&vec![b'a'; 1_000_000])), "34aa973cd4c4daa4f61eeb2bdbad27316534016f");
+    }
+
+    #[test]
+    fn streaming_matches_one_shot() {
+        let message: Vec<u8> = (0..200u8).collect();
+        for split in [0, 1, 55, 56, 63, 64, 65, 128, 200] {
+            let mut hasher = Sha1::new();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..]);
+            assert_eq!(hasher.finalize(), sha1(&message));
+        }
+    }
+}
@@ -1,4 +1,6 @@
-const K: [u32; 64] = [
+use crate::hash::Hasher;
+
+pub(crate) const K: [u32; 64] = [
     0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 
     0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5, 
     0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 
@@ -17,7 +19,7 @@ const K: [u32; 64] = [
     0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2
 ];
 
-const H: [u32; 8] = [
+pub(crate) const H: [u32; 8] = [
     0x6a09e667, 
     0xbb67ae85, 
     0x3c6ef372, 
@@ -28,24 +30,6 @@ const H: [u32; 8] = [
     0x5be0cd19
 ];
 
-fn pad_message(message: &[u8]) -> Vec<u8> {
-    let mut padded = message.to_vec();
-    let message_len_bits = (message.len() * 8) as u64;
-
-    padded.push(0x80);
-    while (padded.len() * 8) % 512 != 448 {
-        padded.push(0x00);
-    }
-
-    padded.extend_from_slice(&message_len_bits.to_be_bytes());
-
-    padded
-}
-
-fn chunk_message(padded: &[u8]) -> Vec<&[u8]> {
-    padded.chunks(64).collect()
-}
-
 fn expand_block(block: &[u8]) -> [u32; 64] {
     let mut w = [0u32; 64];
 
@@ -112,26 +96,125 @@ fn compress_block(w: &[u32; 64], h: &mut [u32; 8]) {
     h[7] = h[7].wrapping_add(hh);
 }
 
-fn finalize_hash(h: &[u32; 8])-> Vec<u8> {
-    h.iter()
-        .flat_map(|&x| x.to_be_bytes().to_vec())
-        .collect()
+#[derive(Clone)]
+pub struct Sha256 {
+    state: [u32; 8],
+    buffer: [u8; 64],
+    buffer_len: usize,
+    length: u64,
 }
 
-pub fn sha256(message: &[u8]) -> Vec<u8> {
-    let padded = pad_message(message);
-    let blocks = chunk_message(&padded);
+impl Hasher for Sha256 {
+    const BLOCK_SIZE: usize = 64;
+    const OUTPUT_SIZE: usize = 32;
+
+    type Output = [u8; 32];
+
+    fn new() -> Self {
+        Sha256 {
+            state: H,
+            buffer: [0u8; 64],
+            buffer_len: 0,
+            length: 0,
+        }
+    }
 
-    let mut h = H;
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u64;
+
+        if self.buffer_len > 0 {
+            let take = (64 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len < 64 {
+                return;
+            }
+            compress_block(&expand_block(&self.buffer), &mut self.state);
+            self.buffer_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(64);
+        for block in &mut blocks {
+            compress_block(&expand_block(block), &mut self.state);
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
 
-    for block in blocks {
-        let w = expand_block(block);
-        compress_block(&w, &mut h);
+    fn finalize(mut self) -> [u8; 32] {
+        let message_len_bits = self.length * 8;
+
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+        if self.buffer_len >= 56 {
+            compress_block(&expand_block(&self.buffer), &mut self.state);
+            self.buffer = [0u8; 64];
+        }
+        self.buffer[56..].copy_from_slice(&message_len_bits.to_be_bytes());
+        compress_block(&expand_block(&self.buffer), &mut self.state);
+
+        let mut digest = [0u8; 32];
+        for (chunk, word) in digest.chunks_exact_mut(4).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
     }
+}
 
-    finalize_hash(&h)
+pub fn sha256(message: &[u8]) -> [u8; 32] {
+    Sha256::digest(message)
 }
 
-pub fn double_sha256(data: &[u8]) -> Vec<u8> {
+pub fn double_sha256(data: &[u8]) -> [u8; 32] {
     sha256(&sha256(data))
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // FIPS 180-4 examples.
+    #[test]
+    fn sha256_vectors() {
+        let cases: [(&[u8], &str); 3] = [
+            (b"", "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"),
+            (b"abc", "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"),
+            (b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq", "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(vec_to_hex(&sha256(message)), expected);
+        }
+        assert_eq!(vec_to_hex(&sha256(&vec![b'a'; 1_000_000])), "cdc76e5c9914fb9281a1c7e284d73e67f1809a48a497200e046d39ccc7112cd0");
+    }
+
+    #[test]
+    fn double_sha256_empty() {
+        assert_eq!(vec_to_hex(&double_sha256(b"")), "5df6e0e2761359d30a8275058e299fcc0381534545f55cf43e41983f5d4c9456");
+    }
+
+    #[test]
+    fn split_updates_match_one_shot() {
+        let message: Vec<u8> = (0..300u32).map(|i| (i * 7 + 3) as u8).collect();
+        for len in [0, 1, 55, 56, 63, 64, 111, 112, 128, 300] {
+            let one_shot = Sha256::digest(&message[..len]);
+            for split in [0, 1, 55, 56, 63, 64, 111, 112, 128] {
+                let split = split.min(len);
+                let mut hasher = Sha256::new();
+                hasher.update(&message[..split]);
+                hasher.update(&message[split..len]);
+                assert_eq!(hasher.finalize(), one_shot);
+            }
+
+            let mut hasher = Sha256::new();
+            for byte in &message[..len] {
+                hasher.update(std::slice::from_ref(byte));
+            }
+            assert_eq!(hasher.finalize(), one_shot);
+        }
+    }
+}
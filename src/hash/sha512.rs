@@ -1,3 +1,5 @@
+use crate::hash::Hasher;
+
 const K: [u64; 80] = [
     0x428a2f98d728ae22, 0x7137449123ef65cd, 0xb5c0fbcfec4d3b2f, 0xe9b5dba58189dbbc,
     0x3956c25bf348b538, 0x59f111f1b605d019, 0x923f82a4af194f9b, 0xab1c5ed5da6d8118,
@@ -33,24 +35,6 @@ const H: [u64; 8] = [
     0x5be0cd19137e2179
 ];
 
-fn pad_message(message: &[u8]) -> Vec<u8> {
-    let mut padded = message.to_vec();
-    let message_len_bits = (message.len() as u128) * 8;
-
-    padded.push(0x80);
-    while (padded.len() * 8) % 1024 != 896 {
-        padded.push(0x00);
-    }
-
-    padded.extend_from_slice(&message_len_bits.to_be_bytes());
-
-    padded
-}
-
-fn chunk_message(padded: &[u8]) -> Vec<&[u8]> {
-    padded.chunks(128).collect()
-}
-
 fn expand_block(block: &[u8]) -> [u64; 80] {
     let mut w = [0u64; 80];
 
@@ -126,22 +110,116 @@ fn compress_block(w: &[u64; 80], h: &mut [u64; 8]) {
     h[7] = h[7].wrapping_add(hh);
 }
 
-fn finalize_hash(h: &[u64; 8]) -> Vec<u8> {
-    h.iter()
-        .flat_map(|&x| x.to_be_bytes().to_vec())
-        .collect()
+#[derive(Clone)]
+pub struct Sha512 {
+    state: [u64; 8],
+    buffer: [u8; 128],
+    buffer_len: usize,
+    length: u128,
 }
 
-pub fn sha512(message: &[u8]) -> Vec<u8> {
-    let padded = pad_message(message);
-    let blocks = chunk_message(&padded);
+impl Hasher for Sha512 {
+    const BLOCK_SIZE: usize = 128;
+    const OUTPUT_SIZE: usize = 64;
 
-    let mut h = H;
+    type Output = [u8; 64];
 
-    for block in blocks {
-        let w = expand_block(block);
-        compress_block(&w, &mut h);
+    fn new() -> Self {
+        Sha512 {
+            state: H,
+            buffer: [0u8; 128],
+            buffer_len: 0,
+            length: 0,
+        }
     }
 
-    finalize_hash(&h)
+    fn update(&mut self, mut data: &[u8]) {
+        self.length += data.len() as u128;
+
+        if self.buffer_len > 0 {
+            let take = (128 - self.buffer_len).min(data.len());
+            self.buffer[self.buffer_len..self.buffer_len + take].copy_from_slice(&data[..take]);
+            self.buffer_len += take;
+            data = &data[take..];
+
+            if self.buffer_len < 128 {
+                return;
+            }
+            compress_block(&expand_block(&self.buffer), &mut self.state);
+            self.buffer_len = 0;
+        }
+
+        let mut blocks = data.chunks_exact(128);
+        for block in &mut blocks {
+            compress_block(&expand_block(block), &mut self.state);
+        }
+
+        let rest = blocks.remainder();
+        self.buffer[..rest.len()].copy_from_slice(rest);
+        self.buffer_len = rest.len();
+    }
+
+    fn finalize(mut self) -> [u8; 64] {
+        let message_len_bits = self.length * 8;
+
+        self.buffer[self.buffer_len] = 0x80;
+        self.buffer[self.buffer_len + 1..].fill(0);
+        if self.buffer_len >= 112 {
+            compress_block(&expand_block(&self.buffer), &mut self.state);
+            self.buffer = [0u8; 128];
+        }
+        self.buffer[112..].copy_from_slice(&message_len_bits.to_be_bytes());
+        compress_block(&expand_block(&self.buffer), &mut self.state);
+
+        let mut digest = [0u8; 64];
+        for (chunk, word) in digest.chunks_exact_mut(8).zip(self.state.iter()) {
+            chunk.copy_from_slice(&word.to_be_bytes());
+        }
+        digest
+    }
+}
+
+pub fn sha512(message: &[u8]) -> [u8; 64] {
+    Sha512::digest(message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::vec_to_hex;
+
+    // FIPS 180-4 examples.
+    #[test]
+    fn sha512_vectors() {
+        let cases: [(&[u8], &str); 3] = [
+            (b"", "cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"),
+            (b"abc", "ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"),
+            (b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmnhijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu", "8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"),
+        ];
+        for (message, expected) in cases {
+            assert_eq!(vec_to_hex(&sha512(message)), expected);
+        }
+        assert_eq!(vec_to_hex(&sha512(&vec![b'a'; 1_000_000])), "e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973ebde0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b");
+    }
+
+    #[test]
+    fn split_updates_match_one_shot() {
+        let message: Vec<u8> = (0..300u32).map(|i| (i * 7 + 3) as u8).collect();
+        for len in [0, 1, 55, 56, 63, 64, 111, 112, 128, 300] {
+            let one_shot = Sha512::digest(&message[..len]);
+            for split in [0, 1, 55, 56, 63, 64, 111, 112, 128] {
+                let split = split.min(len);
+                let mut hasher = Sha512::new();
+                hasher.update(&message[..split]);
+                hasher.update(&message[split..len]);
+                assert_eq!(hasher.finalize(), one_shot);
+            }
+
+            let mut hasher = Sha512::new();
+            for byte in &message[..len] {
+                hasher.update(std::slice::from_ref(byte));
+            }
+            assert_eq!(hasher.finalize(), one_shot);
+        }
+    }
 }
@@ -0,0 +1,143 @@
+use std::sync::OnceLock;
+
+use crate::hash::{Hasher, Sha256, sha256};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HashTag {
+    TapLeaf,
+    TapBranch,
+    TapTweak,
+    TapSighash,
+    Bip340Challenge,
+    Bip340Aux,
+    Bip340Nonce,
+    Bip352Inputs,
+    Bip352SharedSecret,
+    Bip352Label,
+}
+
+const TAG_COUNT: usize = 10;
+
+static MIDSTATES: [OnceLock<Sha256>; TAG_COUNT] = [const { OnceLock::new() }; TAG_COUNT];
+
+impl HashTag {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HashTag::TapLeaf => "TapLeaf",
+            HashTag::TapBranch => "TapBranch",
+            HashTag::TapTweak => "TapTweak",
+            HashTag::TapSighash => "TapSighash",
+            HashTag::Bip340Challenge => "BIP0340/challenge",
+            HashTag::Bip340Aux => "BIP0340/aux",
+            HashTag::Bip340Nonce => "BIP0340/nonce",
+            HashTag::Bip352Inputs => "BIP0352/Inputs",
+            HashTag::Bip352SharedSecret => "BIP0352/SharedSecret",
+            HashTag::Bip352Label => "BIP0352/Label",
+        }
+    }
+
+    pub fn engine(&self) -> Sha256 {
+        MIDSTATES[*self as usize]
+            .get_or_init(|| tagged_engine(self.as_str().as_bytes()))
+            .clone()
+    }
+
+    pub fn hash(&self, message: &[u8]) -> [u8; 32] {
+        let mut engine = self.engine();
+        engine.update(message);
+        engine.finalize()
+    }
+}
+
+pub fn tagged_engine(tag: &[u8]) -> Sha256 {
+    let tag_hash = sha256(tag);
+
+    let mut engine = Sha256::new();
+    engine.update(&tag_hash);
+    engine.update(&tag_hash);
+    engine
+}
+
+pub fn tagged_hash(tag: &[u8], message: &[u8]) -> [u8; 32] {
+    let mut engine = tagged_engine(tag);
+    engine.update(message);
+    engine.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::{Parity, Scalar, Secp256k1, SecretKey};
+    use crate::utils::vec_to_hex;
+
+    const TAGS: [HashTag; TAG_COUNT] = [
+        HashTag::TapLeaf,
+        HashTag::TapBranch,
+        HashTag::TapTweak,
+        HashTag::TapSighash,
+        HashTag::Bip340Challenge,
+        HashTag::Bip340Aux,
+        HashTag::Bip340Nonce,
+        HashTag::Bip352Inputs,
+        HashTag::Bip352SharedSecret,
+        HashTag::Bip352Label,
+    ];
+
+    #[test]
+    fn cached_midstates_match_definition() {
+        for tag in TAGS {
+            let tag_hash = sha256(tag.as_str().as_bytes());
+            for len in [0, 31, 100] {
+                let message = vec![0x5a; len];
+                let mut preimage = [tag_hash, tag_hash].concat();
+                preimage.extend_from_slice(&message);
+
+                let expected = sha256(&preimage);
+                assert_eq!(tag.hash(&message), expected);
+                assert_eq!(tagged_hash(tag.as_str().as_bytes(), &message), expected);
+            }
+        }
+    }
+
+    fn scalar(bytes: [u8; 32]) -> Scalar {
+        Scalar::from_be_bytes(bytes).unwrap()
+    }
+
+    // BIP340 test vector 0, signed with the aux, nonce and challenge tags.
+    #[test]
+    fn bip340_vector_0() {
+        let secp = Secp256k1::new();
+        let message = [0u8; 32];
+        let aux_rand = [0u8; 32];
+
+        let mut secret = [0u8; 32];
+        secret[31] = 3;
+        let mut d = SecretKey::from_slice(&secret).unwrap();
+        let (pubkey, parity) = d.x_only_public_key(&secp);
+        assert_eq!(vec_to_hex(&pubkey.serialize()), "f9308a019258c31049344f85f89d5229b531c845836f99b08601f113bce036f9");
+        if parity == Parity::Odd {
+            d = d.negate();
+        }
+
+        let mut t = d.secret_bytes();
+        for (byte, mask) in t.iter_mut().zip(HashTag::Bip340Aux.hash(&aux_rand)) {
+            *byte ^= mask;
+        }
+        let nonce = HashTag::Bip340Nonce.hash(&[&t[..], &pubkey.serialize(), &message].concat());
+        let mut k = SecretKey::from_slice(&nonce).unwrap();
+        let (r, parity) = k.x_only_public_key(&secp);
+        if parity == Parity::Odd {
+            k = k.negate();
+        }
+
+        let e = HashTag::Bip340Challenge.hash(&[&r.serialize()[..], &pubkey.serialize(), &message].concat());
+        let ed = d.mul_tweak(&scalar(e)).unwrap();
+        let s = k.add_tweak(&scalar(ed.secret_bytes())).unwrap();
+
+        let signature = [&r.serialize()[..], &s.secret_bytes()].concat();
+        assert_eq!(
+            vec_to_hex(&signature),
+            "e907831f80848d1069a5371b402410364bdf1c5f8307b0084c55f1ce2dca821525f66a4a85ea8b71e482a74f382d2ce5ebeee8fdb2172f477df4900d310536c0"
+        );
+    }
+}
@@ -4,7 +4,17 @@ pub mod bip32;
 pub mod bip39;
 pub mod bip44;
 pub mod bip49;
+pub mod bip37;
 pub mod bip84;
+pub mod bip93;
+pub mod bip152;
+pub mod bolt11;
 pub mod utils;
+pub mod consensus;
+pub mod entropy;
+pub mod error;
 pub mod signature;
-pub mod tx;
\ No newline at end of file
+pub mod tx;
+pub mod undo;
+
+pub use error::Error;
@@ -1,32 +1,33 @@
-use wallet::utils::{vec_to_hex, hex_to_vec};
+use wallet::utils::{vec_to_hex, FromHex};
 use wallet::bip84::{generate_master_zprv, derive_child_zprv, zprv_to_zpub, p2wpkh_address_from_zpub};
 
 use wallet::tx::{Tx, TxIn, TxOut, OutPoint, Witness, serialize_transaction, calculate_txid, create_script_pubkey};
+use wallet::hash::Txid;
+use wallet::Error;
 
-fn main() {
+fn main() -> Result<(), Error> {
     let mnemonic = "consider cry bomb sniff party pattern pool horse skirt damage dawn wagon excess slab snow abstract series dad worth frequent lemon imitate nest chicken";
     let passphrase = "";
     
     // Test BIP84: m/84'/0'/0'/0
     println!("\n--- BIP84 (zprv/zpub) ---");
-    let master_zprv = generate_master_zprv(mnemonic, passphrase);
-    let zprv_84 = derive_child_zprv(&master_zprv, 0x80000054);  // m/84'
-    let zprv_84_0 = derive_child_zprv(&zprv_84, 0x80000000);      // m/84'/0'
-    let zprv_84_0_0 = derive_child_zprv(&zprv_84_0, 0x80000000);    // m/84'/0'/0'
-    let zprv_84_0_0_0 = derive_child_zprv(&zprv_84_0_0, 0);          // m/84'/0'/0'/0
-    let zpub_84_0_0_0 = zprv_to_zpub(&zprv_84_0_0_0);
+    let master_zprv = generate_master_zprv(mnemonic, passphrase)?;
+    let zprv_84 = derive_child_zprv(&master_zprv, 0x80000054)?;  // m/84'
+    let zprv_84_0 = derive_child_zprv(&zprv_84, 0x80000000)?;      // m/84'/0'
+    let zprv_84_0_0 = derive_child_zprv(&zprv_84_0, 0x80000000)?;    // m/84'/0'/0'
+    let zprv_84_0_0_0 = derive_child_zprv(&zprv_84_0_0, 0)?;          // m/84'/0'/0'/0
+    let zpub_84_0_0_0 = zprv_to_zpub(&zprv_84_0_0_0)?;
 
 
     // Génération de 3 adresses P2WPKH à partir de la clé publique compressée
-    let address0 = p2wpkh_address_from_zpub(&zpub_84_0_0_0, 0);
-    let address1 = p2wpkh_address_from_zpub(&zpub_84_0_0_0, 1);
+    let address0 = p2wpkh_address_from_zpub(&zpub_84_0_0_0, 0)?;
+    let address1 = p2wpkh_address_from_zpub(&zpub_84_0_0_0, 1)?;
 
     println!("\n--- P2WPKH addresses ---");
     println!("Address 0: {}", address0);
     println!("Address 1: {}", address1);
 
-    let script_pubkey = create_script_pubkey("tb1qrazrspgm7enyw0hcsl90jzcsj6hp0qv4hdd65v")
-        .expect("Invalid script pubkey");
+    let script_pubkey = create_script_pubkey("tb1qrazrspgm7enyw0hcsl90jzcsj6hp0qv4hdd65v")?;
 
     println!("\n--- Script pubkey ---");
     println!("Script pubkey: {}", vec_to_hex(&script_pubkey));
@@ -37,9 +38,7 @@ fn main() {
         inputs: vec![
             TxIn {
                 previous_output: OutPoint {
-                    txid: hex_to_vec("ae20ce9931fc9ed1c1e65e785510c2b58d75ac831fb92ab2925be4f295ff0883")
-                        .try_into()
-                        .expect("Invalid txid"),
+                    txid: Txid::from_hex("ae20ce9931fc9ed1c1e65e785510c2b58d75ac831fb92ab2925be4f295ff0883")?,
                     vout: 1,
                 },
                 script_sig: vec![],
@@ -49,11 +48,11 @@ fn main() {
         outputs: vec![
             TxOut {
                 value: 10_000,
-                script_pubkey: create_script_pubkey("tb1qrazrspgm7enyw0hcsl90jzcsj6hp0qv4hdd65v").expect("Invalid script pubkey"),
+                script_pubkey: create_script_pubkey("tb1qrazrspgm7enyw0hcsl90jzcsj6hp0qv4hdd65v")?,
             },
             TxOut {
                 value: 5_000,
-                script_pubkey: create_script_pubkey("tb1qhzkw7r54krcr3te57dwpq779ygcx8g396ke97g").expect("Invalid script pubkey"),
+                script_pubkey: create_script_pubkey("tb1qhzkw7r54krcr3te57dwpq779ygcx8g396ke97g")?,
             },
         ],
         witnesses: Some(vec![ Witness { items: vec![] } ]),
@@ -61,10 +60,12 @@ fn main() {
     };
 
     let txid = calculate_txid(&tx);
-    println!("Txid: {}", vec_to_hex(&txid));
+    println!("Txid: {}", txid);
 
     let raw_tx = serialize_transaction(&tx, true);
     println!("Raw tx: {}", vec_to_hex(&raw_tx));
+
+    Ok(())
 }
 
 
@@ -1,7 +1,12 @@
-use crate::hash::double_sha256;
-use crate::utils::{write_varint, read_varint, decode_bech32, bech32::convert_bits, hex_to_vec};
+use std::io::{self, Read, Write};
 
-#[derive(Debug, Clone)]
+use crate::hash::{Hash256, Txid, Wtxid};
+use crate::address::{decode_segwit_address, segwit_script_pubkey};
+use crate::consensus::{Encodable, Decodable, EncodeError, serialize, deserialize, impl_vec_encodable};
+use crate::utils::{hex_to_vec, FromHex, ToHex};
+use crate::error::Error;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Tx {
     pub version: u32,
     pub flag: Option<u16>,
@@ -11,195 +16,331 @@ pub struct Tx {
     pub lock_time: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TxIn {
     pub previous_output: OutPoint,
     pub script_sig: Vec<u8>,
     pub sequence: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct TxOut {
     pub value: u64,
     pub script_pubkey: Vec<u8>,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct OutPoint {
-    pub txid: [u8; 32],
+    pub txid: Txid,
     pub vout: u32,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Witness {
     pub items: Vec<Vec<u8>>,
 }
 
-pub fn create_script_pubkey(bech32_address: &str) -> Result<Vec<u8>, String> {
-    let (hrp, data) = decode_bech32(bech32_address)
-        .map_err(|e| format!("Erreur lors du décodage de l'adresse Bech32: {}", e))?;
+pub fn create_script_pubkey(bech32_address: &str) -> Result<Vec<u8>, Error> {
+    let (hrp, witness_version, witness_program) = decode_segwit_address(bech32_address)?;
     if hrp != "bc" && hrp != "tb" {
-        return Err("HRP invalide pour une adresse Bitcoin.".to_string());
+        return Err(Error::Address("Invalid HRP for a Bitcoin address"));
     }
-    if data.is_empty() {
-        return Err("Données vides dans l'adresse Bech32.".to_string());
+
+    segwit_script_pubkey(witness_version, &witness_program)
+}
+
+impl Encodable for OutPoint {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        Ok(self.txid.consensus_encode(writer)? + self.vout.consensus_encode(writer)?)
     }
-    let witness_version = data[0];
-    let witness_program = convert_bits(&data[1..], 5, 8, false)
-        .map_err(|e| format!("Erreur lors de la conversion des bits: {}", e))?;
-    if witness_program.len() != 20 && witness_program.len() != 32 {
-        return Err("Longueur du programme témoin invalide.".to_string());
+}
+
+impl Decodable for OutPoint {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        Ok(OutPoint {
+            txid: Txid::consensus_decode(reader)?,
+            vout: u32::consensus_decode(reader)?,
+        })
     }
-    let mut script_pubkey = Vec::new();
-    script_pubkey.push(witness_version);
-    script_pubkey.push(witness_program.len() as u8);
-    script_pubkey.extend(witness_program);
+}
 
-    Ok(script_pubkey)
+impl Encodable for TxIn {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        let mut len = self.previous_output.consensus_encode(writer)?;
+        len += self.script_sig.consensus_encode(writer)?;
+        len += self.sequence.consensus_encode(writer)?;
+        Ok(len)
+    }
 }
 
-pub fn serialize_transaction(tx: &Tx, include_witness: bool) -> Vec<u8> {
-    let mut raw = Vec::new();
-    raw.extend_from_slice(&tx.version.to_le_bytes());
-    let has_witness = include_witness && tx.witnesses.is_some();
-    if has_witness {
-        raw.push(0x00);
-        raw.push(0x01);
-    }
-
-    write_varint(tx.inputs.len(), &mut raw);
-    for input in &tx.inputs {
-        let mut reversed_txid = input.previous_output.txid;
-        reversed_txid.reverse();
-        raw.extend_from_slice(&reversed_txid);
-        raw.extend_from_slice(&input.previous_output.vout.to_le_bytes());
-        write_varint(input.script_sig.len(), &mut raw);
-        raw.extend_from_slice(&input.script_sig);
-        raw.extend_from_slice(&input.sequence.to_le_bytes());
-    }
-
-    write_varint(tx.outputs.len(), &mut raw);
-    for output in &tx.outputs {
-        raw.extend_from_slice(&output.value.to_le_bytes());
-        write_varint(output.script_pubkey.len(), &mut raw);
-        raw.extend_from_slice(&output.script_pubkey);
-    }
-
-    if has_witness {
-        if let Some(witnesses) = &tx.witnesses {
-            for witness in witnesses {
-                write_varint(witness.items.len(), &mut raw);
-                for item in &witness.items {
-                    write_varint(item.len(), &mut raw);
-                    raw.extend_from_slice(item);
-                }
-            }
+impl Decodable for TxIn {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        Ok(TxIn {
+            previous_output: OutPoint::consensus_decode(reader)?,
+            script_sig: Vec::<u8>::consensus_decode(reader)?,
+            sequence: u32::consensus_decode(reader)?,
+        })
+    }
+}
+
+impl Encodable for TxOut {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        Ok(self.value.consensus_encode(writer)? + self.script_pubkey.consensus_encode(writer)?)
+    }
+}
+
+impl Decodable for TxOut {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        Ok(TxOut {
+            value: u64::consensus_decode(reader)?,
+            script_pubkey: Vec::<u8>::consensus_decode(reader)?,
+        })
+    }
+}
+
+impl Encodable for Witness {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        self.items.consensus_encode(writer)
+    }
+}
+
+impl Decodable for Witness {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        Ok(Witness { items: Vec::<Vec<u8>>::consensus_decode(reader)? })
+    }
+}
+
+impl_vec_encodable!(TxIn, TxOut);
+
+fn encode_transaction<W: Write + ?Sized>(tx: &Tx, writer: &mut W, include_witness: bool) -> io::Result<usize> {
+    let mut len = tx.version.consensus_encode(writer)?;
+
+    let witnesses = tx
+        .witnesses
+        .as_ref()
+        .filter(|witnesses| include_witness && witnesses.iter().any(|witness| !witness.items.is_empty()));
+    if witnesses.is_some() {
+        len += [0x00u8, 0x01].consensus_encode(writer)?;
+    }
+
+    len += tx.inputs.consensus_encode(writer)?;
+    len += tx.outputs.consensus_encode(writer)?;
+
+    if let Some(witnesses) = witnesses {
+        for witness in witnesses {
+            len += witness.consensus_encode(writer)?;
         }
     }
 
-    raw.extend_from_slice(&tx.lock_time.to_le_bytes());
+    len += tx.lock_time.consensus_encode(writer)?;
+    Ok(len)
+}
 
-    raw
+impl Encodable for Tx {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        encode_transaction(self, writer, true)
+    }
 }
 
+impl Decodable for Tx {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let version = u32::consensus_decode(reader)?;
+        let mut inputs = Vec::<TxIn>::consensus_decode(reader)?;
+
+        if !inputs.is_empty() {
+            return Ok(Tx {
+                version,
+                flag: None,
+                inputs,
+                outputs: Vec::<TxOut>::consensus_decode(reader)?,
+                witnesses: None,
+                lock_time: u32::consensus_decode(reader)?,
+            });
+        }
+
+        if u8::consensus_decode(reader)? != 0x01 {
+            return Err(EncodeError::ParseFailed("Unsupported segwit flag"));
+        }
+        inputs = Vec::<TxIn>::consensus_decode(reader)?;
+        let outputs = Vec::<TxOut>::consensus_decode(reader)?;
 
-pub fn calculate_txid(tx: &Tx) -> [u8; 32] {
+        let mut witnesses = Vec::with_capacity(inputs.len());
+        for _ in 0..inputs.len() {
+            witnesses.push(Witness::consensus_decode(reader)?);
+        }
+        if witnesses.iter().all(|witness| witness.items.is_empty()) {
+            return Err(EncodeError::ParseFailed("Superfluous witness record"));
+        }
+
+        Ok(Tx {
+            version,
+            flag: Some(0x0100),
+            inputs,
+            outputs,
+            witnesses: Some(witnesses),
+            lock_time: u32::consensus_decode(reader)?,
+        })
+    }
+}
+
+pub fn serialize_transaction(tx: &Tx, include_witness: bool) -> Vec<u8> {
+    let mut raw = Vec::new();
+    encode_transaction(tx, &mut raw, include_witness).expect("Writing to a Vec never fails");
+    raw
+}
+
+pub fn calculate_txid(tx: &Tx) -> Txid {
     let raw = serialize_transaction(tx, false);
-    let mut hash = double_sha256(&raw);
-    hash.reverse();
-    hash.try_into().expect("Hash must be 32 bytes")
+    Hash256::hash(&raw).into()
 }
 
-fn read_bytes(data: &[u8], len: usize, index: &mut usize) -> Vec<u8> {
-    let result = data[*index..*index + len].to_vec();
-    *index += len;
-    result
+pub fn calculate_wtxid(tx: &Tx) -> Wtxid {
+    let raw = serialize_transaction(tx, true);
+    Hash256::hash(&raw).into()
 }
 
-fn read_u32(data: &[u8], index: &mut usize) -> u32 {
-    let result = u32::from_le_bytes(data[*index..*index + 4].try_into().unwrap());
-    *index += 4;
-    result
+pub fn deserialize_transaction(hex: &str) -> Result<Tx, Error> {
+    let raw = hex_to_vec(hex)?;
+    Ok(deserialize(&raw)?)
 }
 
-fn read_u64(data: &[u8], index: &mut usize) -> u64 {
-    let result = u64::from_le_bytes(data[*index..*index + 8].try_into().unwrap());
-    *index += 8;
-    result
+impl ToHex for Tx {
+    fn to_hex(&self) -> String {
+        serialize_transaction(self, true).to_hex()
+    }
 }
 
-fn read_txid(data: &[u8], index: &mut usize) -> [u8; 32] {
-    let mut txid : [u8; 32] = read_bytes(data, 32, index).try_into().expect("Invalid TXID length");
-    txid.reverse();
-    txid
+impl FromHex for Tx {
+    type Error = Error;
+
+    fn from_hex(hex: &str) -> Result<Self, Error> {
+        deserialize_transaction(hex)
+    }
+}
+
+macro_rules! impl_consensus_hex {
+    ($($ty:ty),*) => {
+        $(
+            impl ToHex for $ty {
+                fn to_hex(&self) -> String {
+                    serialize(self).to_hex()
+                }
+            }
+
+            impl FromHex for $ty {
+                type Error = Error;
+
+                fn from_hex(hex: &str) -> Result<Self, Error> {
+                    let raw = hex_to_vec(hex)?;
+                    Ok(deserialize(&raw)?)
+                }
+            }
+        )*
+    };
 }
 
+impl_consensus_hex!(OutPoint, TxIn, TxOut);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::{vec_to_hex, HexError};
 
-pub fn deserialize_transaction(hex: &str) -> Result<Tx, String> {
-    let raw = hex_to_vec(hex);
-    let mut index = 0;
+    const GENESIS_COINBASE: &str = "01000000010000000000000000000000000000000000000000000000000000000000000000ffffffff4d04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73ffffffff0100f2052a01000000434104678afdb0fe5548271967f1a67130b7105cd6a828e03909a67962e0ea1f61deb649f6bc3f4cef38c4f35504e51ec112de5c384df7ba0b8d578a4c702b6bf11d5fac00000000";
 
-    let version = read_u32(&raw, &mut index);
-    let mut flag = None;
+    // BIP143 native P2WPKH example, signed.
+    const SEGWIT: &str = "01000000000102fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000494830450221008b9d1dc26ba6a9cb62127b02742fa9d754cd3bebf337f7a55d114c8e5cdd30be022040529b194ba3f9281a99f2b1c0a19c0489bc22ede944ccf4ecbab4cc618ef3ed01eeffffffef51e1b804cc89d182d279655c3aa89e815b1b309fe287d9b2b55d57b90ec68a0100000000ffffffff02202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac9093510d000000001976a9143bde42dbee7e4dbe6a21b2d50ce2f0167faa815988ac000247304402203609e17b84f6a7d30c80bfa610b5b4542f32a8a0d5447a12fb1366d7f01cc44a0220573a954c4518331561406f90300e8f3358f51928d43c212a8caed02de67eebee0121025476c2e83188368da1ff3e292e7acafcdb3566bb0ad253f62fc70f07aeee635711000000";
 
-    if raw.len() >= index + 2 && raw[index] == 0x00 && raw[index + 1] == 0x01 {
-        flag = Some(0x0100);
-        index += 2;
+    #[test]
+    fn legacy_round_trip() {
+        let tx = Tx::from_hex(GENESIS_COINBASE).unwrap();
+        assert_eq!(tx.flag, None);
+        assert_eq!(tx.outputs[0].value, 5_000_000_000);
+        assert_eq!(tx.to_hex(), GENESIS_COINBASE);
+        assert_eq!(calculate_txid(&tx).to_hex(), "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b");
+        assert_eq!(calculate_wtxid(&tx).to_hex(), calculate_txid(&tx).to_hex());
     }
 
-    let input_count = read_varint(&raw, &mut index);
-    let mut inputs = Vec::with_capacity(input_count);
-    for _ in 0..input_count {
-        let txid = read_txid(&raw, &mut index);
-        let vout = read_u32(&raw, &mut index);
-        let script_sig_len = read_varint(&raw, &mut index);
-        let script_sig = read_bytes(&raw, script_sig_len, &mut index);
-        let sequence = read_u32(&raw, &mut index);
+    #[test]
+    fn segwit_round_trip() {
+        let tx = Tx::from_hex(SEGWIT).unwrap();
+        assert_eq!(tx.flag, Some(0x0100));
+        assert_eq!(tx.inputs.len(), 2);
+        assert_eq!(tx.inputs[1].previous_output.vout, 1);
+        assert_eq!(tx.lock_time, 0x11);
 
-        inputs.push(TxIn {
-            previous_output: OutPoint { txid, vout },
-            script_sig,
-            sequence,
-        });
+        let witnesses = tx.witnesses.as_ref().unwrap();
+        assert!(witnesses[0].items.is_empty());
+        assert_eq!(witnesses[1].items.len(), 2);
+        assert_eq!(tx.to_hex(), SEGWIT);
+        assert_eq!(vec_to_hex(&serialize(&tx)), SEGWIT);
+
+        let witness_start = SEGWIT.find("88ac000247").unwrap() + 4;
+        let stripped = format!("{}{}{}", &SEGWIT[..8], &SEGWIT[12..witness_start], &SEGWIT[SEGWIT.len() - 8..]);
+        assert_eq!(vec_to_hex(&serialize_transaction(&tx, false)), stripped);
+        assert_ne!(calculate_txid(&tx).to_hex(), calculate_wtxid(&tx).to_hex());
     }
 
-    let output_count = read_varint(&raw, &mut index);
-    let mut outputs = Vec::with_capacity(output_count);
-    for _ in 0..output_count {
-        let value = read_u64(&raw, &mut index);
-        let script_pubkey_len = read_varint(&raw, &mut index);
-        let script_pubkey = read_bytes(&raw, script_pubkey_len, &mut index);
+    #[test]
+    fn rejects_malformed_transactions() {
+        assert!(Tx::from_hex(&SEGWIT[..SEGWIT.len() - 2]).is_err());
+        assert!(Tx::from_hex(&format!("{}00", SEGWIT)).is_err());
+        assert!(Tx::from_hex("0100000000").is_err());
+        assert!(Tx::from_hex(&format!("{}02{}", &SEGWIT[..10], &SEGWIT[12..])).is_err());
+        assert!(deserialize_transaction("0g").is_err());
+    }
 
-        outputs.push(TxOut {
-            value,
-            script_pubkey,
-        });
+    #[test]
+    fn deserialize_transaction_errors() {
+        assert!(matches!(deserialize_transaction("010"), Err(Error::Hex(HexError::OddLength(3)))));
+        assert!(matches!(
+            deserialize_transaction("01zz"),
+            Err(Error::Hex(HexError::InvalidCharacter { character: 'z', position: 2 }))
+        ));
+        for len in [0, 8, 10, 100, GENESIS_COINBASE.len() - 2] {
+            assert!(matches!(deserialize_transaction(&GENESIS_COINBASE[..len]), Err(Error::Encode(_))));
+        }
     }
-    
-    let witnesses = if flag.is_some() {
-        let mut witnesses = Vec::with_capacity(input_count);
-        for _ in 0..input_count {
-            let item_count = read_varint(&raw, &mut index);
-            let mut items = Vec::with_capacity(item_count);
-            for _ in 0..item_count {
-                let item_len = read_varint(&raw, &mut index);
-                items.push(read_bytes(&raw, item_len, &mut index));
-            }
-            witnesses.push(Witness { items });
+
+    #[test]
+    fn superfluous_witness() {
+        let witness_start = SEGWIT.find("88ac000247").unwrap() + 4;
+        let empty = format!("{}0000{}", &SEGWIT[..witness_start], &SEGWIT[SEGWIT.len() - 8..]);
+        assert!(matches!(
+            Tx::from_hex(&empty),
+            Err(Error::Encode(EncodeError::ParseFailed("Superfluous witness record")))
+        ));
+
+        let mut tx = Tx::from_hex(SEGWIT).unwrap();
+        tx.witnesses = Some(vec![Witness { items: Vec::new() }, Witness { items: Vec::new() }]);
+        assert_eq!(tx.to_hex(), vec_to_hex(&serialize_transaction(&tx, false)));
+    }
+
+    #[test]
+    fn component_hex_round_trip() {
+        let tx = Tx::from_hex(SEGWIT).unwrap();
+        let outpoint_hex = "fff7f7881a8099afa6940d42d1e7f6362bec38171ea3edf433541db4e4ad969f00000000";
+        assert_eq!(tx.inputs[0].previous_output.to_hex(), outpoint_hex);
+        assert_eq!(OutPoint::from_hex(outpoint_hex).unwrap(), tx.inputs[0].previous_output);
+
+        for input in &tx.inputs {
+            assert_eq!(&TxIn::from_hex(&input.to_hex()).unwrap(), input);
         }
-        Some(witnesses)
-    } else {
-        None
-    };
+        let output_hex = "202cb206000000001976a9148280b37df378db99f66f85c95a783a76ac7a6d5988ac";
+        assert_eq!(tx.outputs[0].to_hex(), output_hex);
+        assert_eq!(TxOut::from_hex(output_hex).unwrap(), tx.outputs[0]);
+        assert_eq!(TxOut::from_hex(&tx.outputs[1].to_hex()).unwrap(), tx.outputs[1]);
 
-    let lock_time = read_u32(&raw, &mut index);
-
-    Ok(Tx {
-        version,
-        flag,
-        inputs,
-        outputs,
-        witnesses,
-        lock_time,
-    })
-}
\ No newline at end of file
+        assert!(OutPoint::from_hex(&outpoint_hex[..70]).is_err());
+        assert!(OutPoint::from_hex(&format!("{}00", outpoint_hex)).is_err());
+        assert!(TxOut::from_hex(&output_hex[..output_hex.len() - 2]).is_err());
+        assert!(matches!(TxIn::from_hex("0"), Err(Error::Hex(_))));
+    }
+
+    #[test]
+    fn script_pubkey_from_address() {
+        let script_pubkey = create_script_pubkey("bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4").unwrap();
+        assert_eq!(vec_to_hex(&script_pubkey), "0014751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert!(create_script_pubkey("tc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vq5zuyut").is_err());
+    }
+}
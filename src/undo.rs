@@ -0,0 +1,419 @@
+use std::io::{self, Read, Write};
+
+use secp256k1::PublicKey;
+use crate::consensus::{Encodable, Decodable, EncodeError, impl_vec_encodable};
+use crate::hash::{double_sha256, BlockHash};
+use crate::tx::TxOut;
+use crate::utils::CoreVarInt;
+use crate::error::Error;
+
+pub const MAX_SCRIPT_SIZE: usize = 10_000;
+const SPECIAL_SCRIPTS: u64 = 6;
+const OP_RETURN: u8 = 0x6a;
+
+pub fn compress_amount(mut n: u64) -> u64 {
+    if n == 0 {
+        return 0;
+    }
+
+    let mut e = 0;
+    while n.is_multiple_of(10) && e < 9 {
+        n /= 10;
+        e += 1;
+    }
+
+    if e < 9 {
+        let d = n % 10;
+        n /= 10;
+        1 + n.wrapping_mul(9).wrapping_add(d - 1).wrapping_mul(10) + e
+    } else {
+        1 + (n - 1).wrapping_mul(10) + 9
+    }
+}
+
+pub fn decompress_amount(mut x: u64) -> u64 {
+    if x == 0 {
+        return 0;
+    }
+
+    x -= 1;
+    let mut e = x % 10;
+    x /= 10;
+
+    let mut n = if e < 9 {
+        let d = (x % 9) + 1;
+        x /= 9;
+        x.wrapping_mul(10).wrapping_add(d)
+    } else {
+        x + 1
+    };
+    while e > 0 {
+        n = n.wrapping_mul(10);
+        e -= 1;
+    }
+    n
+}
+
+fn special_script_size(code: u64) -> usize {
+    match code {
+        0 | 1 => 20,
+        _ => 32,
+    }
+}
+
+pub fn compress_script(script: &[u8]) -> Option<(u8, Vec<u8>)> {
+    match script {
+        [0x76, 0xa9, 0x14, hash @ .., 0x88, 0xac] if hash.len() == 20 => Some((0x00, hash.to_vec())),
+        [0xa9, 0x14, hash @ .., 0x87] if hash.len() == 20 => Some((0x01, hash.to_vec())),
+        [0x21, pubkey @ .., 0xac] if pubkey.len() == 33 && (pubkey[0] == 0x02 || pubkey[0] == 0x03) => {
+            Some((pubkey[0], pubkey[1..].to_vec()))
+        }
+        [0x41, pubkey @ .., 0xac] if pubkey.len() == 65 && pubkey[0] == 0x04 && PublicKey::from_slice(pubkey).is_ok() => {
+            Some((0x04 | (pubkey[64] & 0x01), pubkey[1..33].to_vec()))
+        }
+        _ => None,
+    }
+}
+
+pub fn decompress_script(code: u8, data: &[u8]) -> Result<Vec<u8>, Error> {
+    let expected = special_script_size(code as u64);
+    if code as u64 >= SPECIAL_SCRIPTS || data.len() != expected {
+        return Err(Error::Undo("Invalid compressed script"));
+    }
+
+    let mut script = Vec::with_capacity(67);
+    match code {
+        0x00 => {
+            script.extend_from_slice(&[0x76, 0xa9, 0x14]);
+            script.extend_from_slice(data);
+            script.extend_from_slice(&[0x88, 0xac]);
+        }
+        0x01 => {
+            script.extend_from_slice(&[0xa9, 0x14]);
+            script.extend_from_slice(data);
+            script.push(0x87);
+        }
+        0x02 | 0x03 => {
+            script.push(0x21);
+            script.push(code);
+            script.extend_from_slice(data);
+            script.push(0xac);
+        }
+        _ => {
+            let mut compressed = [0u8; 33];
+            compressed[0] = code - 2;
+            compressed[1..].copy_from_slice(data);
+            let pubkey = PublicKey::from_slice(&compressed).map_err(|_| Error::Undo("Invalid compressed public key"))?;
+            script.push(0x41);
+            script.extend_from_slice(&pubkey.serialize_uncompressed());
+            script.push(0xac);
+        }
+    }
+    Ok(script)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompressedTxOut(pub TxOut);
+
+impl Encodable for CompressedTxOut {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        let mut len = CoreVarInt(compress_amount(self.0.value)).consensus_encode(writer)?;
+        match compress_script(&self.0.script_pubkey) {
+            Some((code, data)) => {
+                len += code.consensus_encode(writer)?;
+                writer.write_all(&data)?;
+                len += data.len();
+            }
+            None => {
+                let script = &self.0.script_pubkey;
+                len += CoreVarInt(script.len() as u64 + SPECIAL_SCRIPTS).consensus_encode(writer)?;
+                writer.write_all(script)?;
+                len += script.len();
+            }
+        }
+        Ok(len)
+    }
+}
+
+impl Decodable for CompressedTxOut {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let value = decompress_amount(CoreVarInt::consensus_decode(reader)?.0);
+
+        let code = CoreVarInt::consensus_decode(reader)?.0;
+        let script_pubkey = if code < SPECIAL_SCRIPTS {
+            let mut data = vec![0u8; special_script_size(code)];
+            reader.read_exact(&mut data)?;
+            decompress_script(code as u8, &data)
+                .map_err(|_| EncodeError::ParseFailed("Invalid compressed script"))?
+        } else {
+            let size = (code - SPECIAL_SCRIPTS) as usize;
+            if size > MAX_SCRIPT_SIZE {
+                io::copy(&mut reader.take(size as u64), &mut io::sink())?;
+                vec![OP_RETURN]
+            } else {
+                let mut script = vec![0u8; size];
+                reader.read_exact(&mut script)?;
+                script
+            }
+        };
+
+        Ok(CompressedTxOut(TxOut { value, script_pubkey }))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coin {
+    pub output: TxOut,
+    pub height: u32,
+    pub is_coinbase: bool,
+}
+
+impl Encodable for Coin {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        let code = (self.height as u64) * 2 + self.is_coinbase as u64;
+        let mut len = CoreVarInt(code).consensus_encode(writer)?;
+        if self.height > 0 {
+            len += 0u8.consensus_encode(writer)?;
+        }
+        len += CompressedTxOut(self.output.clone()).consensus_encode(writer)?;
+        Ok(len)
+    }
+}
+
+impl Decodable for Coin {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let code = CoreVarInt::consensus_decode(reader)?.0;
+        let height = u32::try_from(code >> 1).map_err(|_| EncodeError::ParseFailed("Coin height overflow"))?;
+        if height > 0 {
+            CoreVarInt::consensus_decode(reader)?;
+        }
+
+        Ok(Coin {
+            output: CompressedTxOut::consensus_decode(reader)?.0,
+            height,
+            is_coinbase: code & 1 == 1,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TxUndo {
+    pub prevouts: Vec<Coin>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BlockUndo {
+    pub txs: Vec<TxUndo>,
+}
+
+impl_vec_encodable!(Coin, TxUndo);
+
+impl Encodable for TxUndo {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        self.prevouts.consensus_encode(writer)
+    }
+}
+
+impl Decodable for TxUndo {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        Ok(TxUndo { prevouts: Vec::<Coin>::consensus_decode(reader)? })
+    }
+}
+
+impl Encodable for BlockUndo {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        self.txs.consensus_encode(writer)
+    }
+}
+
+impl Decodable for BlockUndo {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        Ok(BlockUndo { txs: Vec::<TxUndo>::consensus_decode(reader)? })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UndoRecord {
+    pub block_undo: BlockUndo,
+    pub checksum: [u8; 32],
+    payload: Vec<u8>,
+}
+
+impl UndoRecord {
+    pub fn verify_checksum(&self, prev_block_hash: &BlockHash) -> bool {
+        let mut data = Vec::with_capacity(32 + self.payload.len());
+        data.extend_from_slice(prev_block_hash.as_byte_array());
+        data.extend_from_slice(&self.payload);
+        double_sha256(&data) == self.checksum
+    }
+}
+
+pub fn deobfuscate(data: &mut [u8], key: &[u8; 8], file_offset: u64) {
+    for (i, byte) in data.iter_mut().enumerate() {
+        *byte ^= key[((file_offset + i as u64) % 8) as usize];
+    }
+}
+
+pub fn parse_undo_file(data: &[u8], magic: &[u8; 4]) -> Result<Vec<UndoRecord>, EncodeError> {
+    let mut records = Vec::new();
+    let mut reader = data;
+
+    while reader.len() >= 8 && reader[..4] != [0u8; 4] {
+        if reader[..4] != magic[..] {
+            return Err(EncodeError::ParseFailed("Invalid undo record magic"));
+        }
+        reader = &reader[4..];
+
+        let size = u32::consensus_decode(&mut reader)? as usize;
+        if size > reader.len().saturating_sub(32) {
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into());
+        }
+
+        let (payload, mut rest) = reader.split_at(size);
+        let mut payload_reader = payload;
+        let block_undo = BlockUndo::consensus_decode(&mut payload_reader)?;
+        if !payload_reader.is_empty() {
+            return Err(EncodeError::ParseFailed("Trailing data in undo record"));
+        }
+
+        let checksum = <[u8; 32]>::consensus_decode(&mut rest)?;
+        records.push(UndoRecord {
+            block_undo,
+            checksum,
+            payload: payload.to_vec(),
+        });
+        reader = rest;
+    }
+
+    Ok(records)
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::{deserialize, serialize};
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    const COIN: u64 = 100_000_000;
+    const MAGIC: [u8; 4] = [0xf9, 0xbe, 0xb4, 0xd9];
+    const P2PKH: &str = "76a914751e76e8199196d454941c45d1b3a323f1433bd688ac";
+    const PUBKEY: &str = "0479be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8";
+
+    // Bitcoin Core compress_tests amount vectors.
+    #[test]
+    fn compress_amount_vectors() {
+        let cases = [(0, 0x0), (1, 0x1), (1_000_000, 0x7), (COIN, 0x9), (50 * COIN, 0x32), (21_000_000 * COIN, 0x1406f40)];
+        for (amount, compressed) in cases {
+            assert_eq!(compress_amount(amount), compressed);
+            assert_eq!(decompress_amount(compressed), amount);
+        }
+    }
+
+    #[test]
+    fn compress_amount_round_trip() {
+        for x in 0..100_000 {
+            assert_eq!(compress_amount(decompress_amount(x)), x);
+        }
+        for amount in (0..10_000_000_000).step_by(9_999_991) {
+            assert_eq!(decompress_amount(compress_amount(amount)), amount);
+        }
+    }
+
+    #[test]
+    fn compress_script_kinds() {
+        let pubkey = hex_to_vec(PUBKEY).unwrap();
+        let mut p2pk_uncompressed = vec![0x41];
+        p2pk_uncompressed.extend_from_slice(&pubkey);
+        p2pk_uncompressed.push(0xac);
+        let mut p2pk_compressed = vec![0x21, 0x02];
+        p2pk_compressed.extend_from_slice(&pubkey[1..33]);
+        p2pk_compressed.push(0xac);
+
+        let cases = [
+            (hex_to_vec(P2PKH).unwrap(), Some(0x00)),
+            (hex_to_vec("a914751e76e8199196d454941c45d1b3a323f1433bd687").unwrap(), Some(0x01)),
+            (p2pk_compressed, Some(0x02)),
+            (p2pk_uncompressed, Some(0x04)),
+            (hex_to_vec("0014751e76e8199196d454941c45d1b3a323f1433bd6").unwrap(), None),
+        ];
+        for (script, code) in cases {
+            let compressed = compress_script(&script);
+            assert_eq!(compressed.as_ref().map(|(code, _)| *code), code);
+            if let Some((code, data)) = compressed {
+                assert_eq!(decompress_script(code, &data).unwrap(), script);
+            }
+
+            let output = TxOut { value: 12345, script_pubkey: script };
+            let encoded = serialize(&CompressedTxOut(output.clone()));
+            assert_eq!(deserialize::<CompressedTxOut>(&encoded).unwrap().0, output);
+        }
+
+        assert!(decompress_script(0x06, &[0u8; 32]).is_err());
+        assert!(decompress_script(0x00, &[0u8; 32]).is_err());
+        assert!(decompress_script(0x04, &[0u8; 32]).is_err());
+    }
+
+    #[test]
+    fn coin_encoding() {
+        let coin = Coin {
+            output: TxOut { value: 50 * COIN, script_pubkey: hex_to_vec(P2PKH).unwrap() },
+            height: 1,
+            is_coinbase: true,
+        };
+        let encoded = serialize(&coin);
+        assert_eq!(vec_to_hex(&encoded), "03003200751e76e8199196d454941c45d1b3a323f1433bd6");
+        assert_eq!(deserialize::<Coin>(&encoded).unwrap(), coin);
+    }
+
+    fn undo_record(block_undo: &BlockUndo, prev_block_hash: &BlockHash) -> Vec<u8> {
+        let payload = serialize(block_undo);
+        let mut checksum_data = prev_block_hash.as_byte_array().to_vec();
+        checksum_data.extend_from_slice(&payload);
+
+        let mut record = MAGIC.to_vec();
+        record.extend_from_slice(&(payload.len() as u32).to_le_bytes());
+        record.extend_from_slice(&payload);
+        record.extend_from_slice(&double_sha256(&checksum_data));
+        record
+    }
+
+    #[test]
+    fn undo_file_round_trip() {
+        let coin = Coin {
+            output: TxOut { value: 50 * COIN, script_pubkey: hex_to_vec(P2PKH).unwrap() },
+            height: 1,
+            is_coinbase: true,
+        };
+        let spent = Coin { height: 0, is_coinbase: false, ..coin.clone() };
+        let block_undo = BlockUndo { txs: vec![TxUndo { prevouts: vec![coin, spent] }, TxUndo::default()] };
+        let prev_block_hash = BlockHash::from_byte_array([7u8; 32]);
+
+        let record = undo_record(&block_undo, &prev_block_hash);
+        let mut file = record.repeat(2);
+        file.extend_from_slice(&[0u8; 100]);
+
+        let records = parse_undo_file(&file, &MAGIC).unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].block_undo, block_undo);
+        assert!(records[1].verify_checksum(&prev_block_hash));
+        assert!(!records[1].verify_checksum(&BlockHash::all_zeros()));
+
+        assert!(parse_undo_file(&record[..record.len() - 1], &MAGIC).is_err());
+        assert!(parse_undo_file(&record, &[0x0b, 0x11, 0x09, 0x07]).is_err());
+    }
+
+    #[test]
+    fn deobfuscate_with_offset() {
+        let key = [1, 2, 3, 4, 5, 6, 7, 8];
+        let data: Vec<u8> = (0..40).collect();
+
+        let mut obfuscated = data.clone();
+        deobfuscate(&mut obfuscated, &key, 0);
+        assert_ne!(obfuscated, data);
+
+        let mut tail = obfuscated[5..].to_vec();
+        deobfuscate(&mut tail, &key, 5);
+        assert_eq!(tail, data[5..]);
+    }
+}
@@ -1,41 +1,282 @@
+use std::fmt;
+
+use crate::hash::double_sha256;
+
+const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Base58Error {
+    InvalidCharacter { character: char, position: usize },
+    InvalidChecksum { expected: [u8; 4], found: [u8; 4] },
+    TooShort(usize),
+    InvalidLength { expected: usize, found: usize },
+}
+
+impl fmt::Display for Base58Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base58Error::InvalidCharacter { character, position } => {
+                write!(f, "Invalid Base58 character '{}' at position {}", character, position)
+            }
+            Base58Error::InvalidChecksum { expected, found } => {
+                write!(f, "Invalid Base58Check checksum: expected {:02x?}, found {:02x?}", expected, found)
+            }
+            Base58Error::TooShort(len) => {
+                write!(f, "Base58Check data too short: {} bytes", len)
+            }
+            Base58Error::InvalidLength { expected, found } => {
+                write!(f, "Invalid decoded length: expected {} bytes, found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for Base58Error {}
+
+const BASE58_DIGITS: [i8; 128] = [
+    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+    -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
+    -1,  0,  1,  2,  3,  4,  5,  6,  7,  8, -1, -1, -1, -1, -1, -1,
+    -1,  9, 10, 11, 12, 13, 14, 15, 16, -1, 17, 18, 19, 20, 21, -1,
+    22, 23, 24, 25, 26, 27, 28, 29, 30, 31, 32, -1, -1, -1, -1, -1,
+    -1, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, -1, 44, 45, 46,
+    47, 48, 49, 50, 51, 52, 53, 54, 55, 56, 57, -1, -1, -1, -1, -1
+];
+
+const LIMB_DIGITS: usize = 10;
+const LIMB_BASE: u64 = 430_804_206_899_405_824;
+
 pub fn base58_encode(data: &[u8]) -> String {
-    const BASE58_ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+    let leading_zeros = data.iter().take_while(|&&byte| byte == 0).count();
+    let data = &data[leading_zeros..];
+
+    let mut limbs = vec![0u64; data.len() * 8 / 58 + 2];
+    let mut used = 0;
 
-    let mut leading_zeros = 0;
-    for &byte in data {
-        if byte == 0 {
-            leading_zeros += 1;
+    let head = data.len() % 8;
+    let chunks = std::iter::once(&data[..head]).chain(data[head..].chunks_exact(8));
+    for chunk in chunks.filter(|chunk| !chunk.is_empty()) {
+        let mut carry = chunk.iter().fold(0u128, |acc, &byte| (acc << 8) | byte as u128);
+        let bits = chunk.len() * 8;
+
+        for limb in limbs[..used].iter_mut() {
+            let acc = ((*limb as u128) << bits) + carry;
+            *limb = (acc % LIMB_BASE as u128) as u64;
+            carry = acc / LIMB_BASE as u128;
+        }
+        while carry > 0 {
+            limbs[used] = (carry % LIMB_BASE as u128) as u64;
+            carry /= LIMB_BASE as u128;
+            used += 1;
+        }
+    }
+
+    let mut encoded = Vec::with_capacity(leading_zeros + used * LIMB_DIGITS);
+    encoded.extend(std::iter::repeat_n(b'1', leading_zeros));
+
+    for (i, &limb) in limbs[..used].iter().rev().enumerate() {
+        let mut digits = [0u8; LIMB_DIGITS];
+        let mut value = limb;
+        for digit in digits.iter_mut().rev() {
+            *digit = (value % 58) as u8;
+            value /= 58;
+        }
+
+        let start = if i == 0 {
+            digits.iter().position(|&d| d != 0).unwrap_or(LIMB_DIGITS)
         } else {
-            break;
+            0
+        };
+        encoded.extend(digits[start..].iter().map(|&d| BASE58_ALPHABET[d as usize]));
+    }
+
+    encoded.into_iter().map(char::from).collect()
+}
+
+pub fn base58_decode(input: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut digits = Vec::with_capacity(input.len());
+    for (position, character) in input.chars().enumerate() {
+        let digit = if (character as u32) < 128 { BASE58_DIGITS[character as usize] } else { -1 };
+        if digit < 0 {
+            return Err(Base58Error::InvalidCharacter { character, position });
         }
+        digits.push(digit as u8);
     }
 
-    let mut encoded = Vec::new();
-    let mut num = data.to_vec();
+    let leading_zeros = digits.iter().take_while(|&&digit| digit == 0).count();
+    let digits = &digits[leading_zeros..];
 
-    while !num.is_empty() {
-        let mut remainder = 0u16;
-        let mut new_num = Vec::new();
+    let mut limbs = vec![0u64; digits.len() * 6 / 64 + 2];
+    let mut used = 0;
 
-        for &byte in &num {
-            let temp = (remainder << 8) + byte as u16;
-            let quotient = temp / 58;
-            remainder = temp % 58;
+    let head = digits.len() % LIMB_DIGITS;
+    let groups = std::iter::once(&digits[..head]).chain(digits[head..].chunks_exact(LIMB_DIGITS));
+    for group in groups.filter(|group| !group.is_empty()) {
+        let multiplier = 58u128.pow(group.len() as u32);
+        let mut carry = group.iter().fold(0u128, |acc, &digit| acc * 58 + digit as u128);
 
-            if !new_num.is_empty() || quotient != 0 {
-                new_num.push(quotient as u8);
+        for limb in limbs[..used].iter_mut() {
+            let acc = *limb as u128 * multiplier + carry;
+            *limb = acc as u64;
+            carry = acc >> 64;
+        }
+        while carry > 0 {
+            limbs[used] = carry as u64;
+            carry >>= 64;
+            used += 1;
+        }
+    }
+
+    let mut decoded = Vec::with_capacity(leading_zeros + used * 8);
+    decoded.extend(std::iter::repeat_n(0u8, leading_zeros));
+
+    for (i, limb) in limbs[..used].iter().rev().enumerate() {
+        let bytes = limb.to_be_bytes();
+        let start = if i == 0 {
+            bytes.iter().position(|&b| b != 0).unwrap_or(8)
+        } else {
+            0
+        };
+        decoded.extend_from_slice(&bytes[start..]);
+    }
+
+    Ok(decoded)
+}
+
+pub fn base58_checksum(payload: &[u8]) -> [u8; 4] {
+    let hash = double_sha256(payload);
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+pub fn base58check_encode(payload: &[u8]) -> String {
+    let mut data = Vec::with_capacity(payload.len() + 4);
+    data.extend_from_slice(payload);
+    data.extend_from_slice(&base58_checksum(payload));
+    base58_encode(&data)
+}
+
+pub fn base58check_decode(input: &str) -> Result<Vec<u8>, Base58Error> {
+    let mut data = base58_decode(input)?;
+    if data.len() < 4 {
+        return Err(Base58Error::TooShort(data.len()));
+    }
+
+    let split = data.len() - 4;
+    let expected = base58_checksum(&data[..split]);
+    let found = [data[split], data[split + 1], data[split + 2], data[split + 3]];
+    if expected != found {
+        return Err(Base58Error::InvalidChecksum { expected, found });
+    }
+
+    data.truncate(split);
+    Ok(data)
+}
+
+pub fn base58check_decode_len(input: &str, expected: usize) -> Result<Vec<u8>, Base58Error> {
+    let payload = base58check_decode(input)?;
+    if payload.len() != expected {
+        return Err(Base58Error::InvalidLength { expected, found: payload.len() });
+    }
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::hex_to_vec;
+
+    const XPRV: &str = "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi";
+
+    // Byte-at-a-time division, as used before the limb-based codec.
+    fn reference_encode(data: &[u8]) -> String {
+        let leading_zeros = data.iter().take_while(|&&byte| byte == 0).count();
+        let mut encoded = Vec::new();
+        let mut num = data[leading_zeros..].to_vec();
+        while !num.is_empty() {
+            let mut remainder = 0u16;
+            let mut new_num = Vec::new();
+            for &byte in &num {
+                let temp = (remainder << 8) + byte as u16;
+                remainder = temp % 58;
+                if !new_num.is_empty() || temp / 58 != 0 {
+                    new_num.push((temp / 58) as u8);
+                }
             }
+            encoded.push(BASE58_ALPHABET[remainder as usize]);
+            num = new_num;
+        }
+        encoded.extend(std::iter::repeat_n(b'1', leading_zeros));
+        encoded.reverse();
+        String::from_utf8(encoded).unwrap()
+    }
+
+    #[test]
+    fn base58_vectors() {
+        let cases: [(&[u8], &str); 7] = [
+            (b"", ""),
+            (&[0], "1"),
+            (&[0, 0, 0, 1], "1112"),
+            (&[0x39], "z"),
+            (&[0x3a], "21"),
+            (b"Hello World!", "2NEpo7TZRRrLZSi2U"),
+            (&[0xff; 8], "jpXCZedGfVQ"),
+        ];
+        for (data, expected) in cases {
+            assert_eq!(base58_encode(data), expected);
+            assert_eq!(base58_decode(expected).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn base58_matches_reference_encoder() {
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        for len in 0..300 {
+            let mut data: Vec<u8> = (0..len)
+                .map(|_| {
+                    state ^= state << 13;
+                    state ^= state >> 7;
+                    state ^= state << 17;
+                    state as u8
+                })
+                .collect();
+            data[..(len % 5).min(len)].fill(0);
+
+            let encoded = base58_encode(&data);
+            assert_eq!(encoded, reference_encode(&data));
+            assert_eq!(base58_decode(&encoded).unwrap(), data);
         }
+    }
 
-        encoded.push(BASE58_ALPHABET[remainder as usize]);
-        num = new_num;
+    #[test]
+    fn base58check_round_trip() {
+        let payload = base58check_decode(XPRV).unwrap();
+        assert_eq!(payload.len(), 78);
+        assert_eq!(base58check_encode(&payload), XPRV);
+
+        let payload = hex_to_vec("00eb15231dfceb60925886b67d065299925915aeb1").unwrap();
+        assert_eq!(base58check_encode(&payload), "1NS17iag9jJgTHD1VXjvLCEnZuQ3rJED9L");
+        assert_eq!(base58check_decode_len("1NS17iag9jJgTHD1VXjvLCEnZuQ3rJED9L", 21).unwrap(), payload);
     }
 
-    for _ in 0..leading_zeros {
-        encoded.push(b'1');
+    #[test]
+    fn base58check_bad_checksum() {
+        let corrupted = format!("{}j", &XPRV[..XPRV.len() - 1]);
+        assert!(matches!(base58check_decode(&corrupted), Err(Base58Error::InvalidChecksum { .. })));
     }
 
-    encoded.reverse();
+    #[test]
+    fn base58check_invalid_length() {
+        assert_eq!(
+            base58check_decode_len("1NS17iag9jJgTHD1VXjvLCEnZuQ3rJED9L", 20),
+            Err(Base58Error::InvalidLength { expected: 20, found: 21 })
+        );
+        assert_eq!(base58check_decode("111"), Err(Base58Error::TooShort(3)));
+    }
 
-    String::from_utf8(encoded).expect("Invalid UTF-8 encoding")
-}
\ No newline at end of file
+    #[test]
+    fn base58_invalid_character() {
+        assert_eq!(base58_decode("11OI"), Err(Base58Error::InvalidCharacter { character: 'O', position: 2 }));
+        assert_eq!(base58_decode("1é"), Err(Base58Error::InvalidCharacter { character: 'é', position: 1 }));
+    }
+}
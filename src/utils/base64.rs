@@ -1,74 +1,284 @@
-pub fn base64_encode(data: &[u8]) -> String {
-    let base64_chars = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+use std::fmt;
+use std::io::{self, Write};
 
-    let mut encoded = String::new();
-    let mut buffer: u32;
-    let mut padding = 0;
+const STANDARD_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const URL_SAFE_ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
 
-    let mut i = 0;
-    while i < data.len() {
-        buffer = (data[i] as u32) << 16;
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Base64Variant {
+    Standard,
+    StandardNoPad,
+    UrlSafe,
+    UrlSafeNoPad,
+}
 
-        if i + 1 < data.len() {
-            buffer |= (data[i + 1] as u32) << 8;
-        } else {
-            padding += 1;
+impl Base64Variant {
+    fn alphabet(&self) -> &'static [u8; 64] {
+        match self {
+            Base64Variant::Standard | Base64Variant::StandardNoPad => STANDARD_ALPHABET,
+            Base64Variant::UrlSafe | Base64Variant::UrlSafeNoPad => URL_SAFE_ALPHABET,
         }
+    }
 
-        if i + 2 < data.len() {
-            buffer |= data[i + 2] as u32;
-        } else {
-            padding += 1;
-        }
+    fn padded(&self) -> bool {
+        matches!(self, Base64Variant::Standard | Base64Variant::UrlSafe)
+    }
+}
 
-        encoded.push(base64_chars[((buffer >> 18) & 0x3F) as usize] as char);
-        encoded.push(base64_chars[((buffer >> 12) & 0x3F) as usize] as char);
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Base64Error {
+    InvalidCharacter { character: char, position: usize },
+    InvalidLength(usize),
+    InvalidPadding,
+    NonCanonical,
+}
 
-        if padding < 2 {
-            encoded.push(base64_chars[((buffer >> 6) & 0x3F) as usize] as char);
-        } else {
-            encoded.push('=');
+impl fmt::Display for Base64Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Base64Error::InvalidCharacter { character, position } => {
+                write!(f, "Invalid Base64 character '{}' at position {}", character, position)
+            }
+            Base64Error::InvalidLength(len) => write!(f, "Invalid Base64 length: {}", len),
+            Base64Error::InvalidPadding => write!(f, "Invalid Base64 padding"),
+            Base64Error::NonCanonical => write!(f, "Non-canonical Base64 encoding: trailing bits are not zero"),
         }
+    }
+}
 
-        if padding < 1 {
-            encoded.push(base64_chars[(buffer & 0x3F) as usize] as char);
-        } else {
-            encoded.push('=');
-        }
+impl std::error::Error for Base64Error {}
+
+fn encode_into(data: &[u8], variant: Base64Variant, out: &mut Vec<u8>) {
+    let alphabet = variant.alphabet();
 
-        i += 3;
+    let mut chunks = data.chunks_exact(3);
+    for chunk in &mut chunks {
+        let buffer = (chunk[0] as u32) << 16 | (chunk[1] as u32) << 8 | chunk[2] as u32;
+        out.push(alphabet[((buffer >> 18) & 0x3F) as usize]);
+        out.push(alphabet[((buffer >> 12) & 0x3F) as usize]);
+        out.push(alphabet[((buffer >> 6) & 0x3F) as usize]);
+        out.push(alphabet[(buffer & 0x3F) as usize]);
     }
 
-    encoded
+    let rest = chunks.remainder();
+    if rest.is_empty() {
+        return;
+    }
+
+    let mut buffer = (rest[0] as u32) << 16;
+    if rest.len() == 2 {
+        buffer |= (rest[1] as u32) << 8;
+    }
+
+    out.push(alphabet[((buffer >> 18) & 0x3F) as usize]);
+    out.push(alphabet[((buffer >> 12) & 0x3F) as usize]);
+    if rest.len() == 2 {
+        out.push(alphabet[((buffer >> 6) & 0x3F) as usize]);
+    }
+    if variant.padded() {
+        out.extend(std::iter::repeat_n(b'=', 3 - rest.len()));
+    }
 }
 
-pub fn base64_decode(input: &str) -> Vec<u8> {
-    let base64_chars = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
-    let mut output = Vec::new();
-    let mut buffer = 0u32;
-    let mut bits_collected = 0;
+pub fn base64_encode_variant(data: &[u8], variant: Base64Variant) -> String {
+    let mut encoded = Vec::with_capacity(data.len().div_ceil(3) * 4);
+    encode_into(data, variant, &mut encoded);
+    encoded.into_iter().map(char::from).collect()
+}
 
-    for byte in input.bytes() {
-        if byte == b'\n' || byte == b'\r' || byte == b' ' {
-            continue;
-        }
+pub fn base64_encode(data: &[u8]) -> String {
+    base64_encode_variant(data, Base64Variant::Standard)
+}
 
-        if byte == b'=' {
-            break;
+pub fn base64_decode_variant(input: &str, variant: Base64Variant) -> Result<Vec<u8>, Base64Error> {
+    let alphabet = variant.alphabet();
+    let bytes = input.as_bytes();
+
+    let data_len = if variant.padded() {
+        if !bytes.len().is_multiple_of(4) {
+            return Err(Base64Error::InvalidLength(bytes.len()));
         }
+        let padding = bytes.iter().rev().take(2).take_while(|&&b| b == b'=').count();
+        bytes.len() - padding
+    } else {
+        bytes.len()
+    };
+
+    if data_len % 4 == 1 {
+        return Err(Base64Error::InvalidLength(bytes.len()));
+    }
+    if variant.padded() && bytes.len() - data_len > 0 && data_len % 4 == 0 {
+        return Err(Base64Error::InvalidPadding);
+    }
 
-        let val = base64_chars.iter().position(|&c| c == byte)
-            .expect("Invalid Base64 character");
+    let mut output = Vec::with_capacity(data_len * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits_collected = 0;
+
+    for (position, character) in input.char_indices().take_while(|&(i, _)| i < data_len) {
+        let val = alphabet
+            .iter()
+            .position(|&c| c as char == character)
+            .ok_or(if character == '=' {
+                Base64Error::InvalidPadding
+            } else {
+                Base64Error::InvalidCharacter { character, position }
+            })?;
 
         buffer = (buffer << 6) | (val as u32);
         bits_collected += 6;
 
         if bits_collected >= 8 {
             bits_collected -= 8;
-            let byte = (buffer >> bits_collected) as u8;
-            output.push(byte);
+            output.push((buffer >> bits_collected) as u8);
+        }
+    }
+
+    if buffer & ((1 << bits_collected) - 1) != 0 {
+        return Err(Base64Error::NonCanonical);
+    }
+
+    Ok(output)
+}
+
+pub fn base64_decode(input: &str) -> Result<Vec<u8>, Base64Error> {
+    base64_decode_variant(input, Base64Variant::Standard)
+}
+
+pub struct Base64Encoder<W: Write> {
+    writer: W,
+    variant: Base64Variant,
+    pending: [u8; 3],
+    pending_len: usize,
+    buffer: Vec<u8>,
+}
+
+impl<W: Write> Base64Encoder<W> {
+    pub fn new(writer: W, variant: Base64Variant) -> Self {
+        Base64Encoder {
+            writer,
+            variant,
+            pending: [0u8; 3],
+            pending_len: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    pub fn finish(mut self) -> io::Result<W> {
+        self.buffer.clear();
+        encode_into(&self.pending[..self.pending_len], self.variant, &mut self.buffer);
+        self.writer.write_all(&self.buffer)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+}
+
+impl<W: Write> Write for Base64Encoder<W> {
+    fn write(&mut self, mut data: &[u8]) -> io::Result<usize> {
+        let written = data.len();
+        self.buffer.clear();
+
+        if self.pending_len > 0 {
+            let take = (3 - self.pending_len).min(data.len());
+            self.pending[self.pending_len..self.pending_len + take].copy_from_slice(&data[..take]);
+            self.pending_len += take;
+            data = &data[take..];
+
+            if self.pending_len < 3 {
+                return Ok(written);
+            }
+            encode_into(&self.pending, self.variant, &mut self.buffer);
+            self.pending_len = 0;
+        }
+
+        let full = data.len() - data.len() % 3;
+        encode_into(&data[..full], self.variant, &mut self.buffer);
+
+        let rest = &data[full..];
+        self.pending[..rest.len()].copy_from_slice(rest);
+        self.pending_len = rest.len();
+
+        self.writer.write_all(&self.buffer)?;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // RFC4648 section 10.
+    const VECTORS: [(&str, &str); 7] = [
+        ("", ""),
+        ("f", "Zg=="),
+        ("fo", "Zm8="),
+        ("foo", "Zm9v"),
+        ("foob", "Zm9vYg=="),
+        ("fooba", "Zm9vYmE="),
+        ("foobar", "Zm9vYmFy"),
+    ];
+
+    #[test]
+    fn rfc4648_vectors() {
+        for (plain, encoded) in VECTORS {
+            assert_eq!(base64_encode(plain.as_bytes()), encoded);
+            assert_eq!(base64_decode(encoded).unwrap(), plain.as_bytes());
+
+            let unpadded = encoded.trim_end_matches('=');
+            assert_eq!(base64_encode_variant(plain.as_bytes(), Base64Variant::StandardNoPad), unpadded);
+            assert_eq!(base64_decode_variant(unpadded, Base64Variant::UrlSafeNoPad).unwrap(), plain.as_bytes());
         }
     }
 
-    output
-}
\ No newline at end of file
+    #[test]
+    fn url_safe_alphabet() {
+        assert_eq!(base64_encode(&[0xfb, 0xff]), "+/8=");
+        assert_eq!(base64_encode_variant(&[0xfb, 0xff], Base64Variant::UrlSafe), "-_8=");
+        assert_eq!(base64_decode_variant("-_8=", Base64Variant::UrlSafe).unwrap(), [0xfb, 0xff]);
+        assert_eq!(base64_decode("-_8="), Err(Base64Error::InvalidCharacter { character: '-', position: 0 }));
+    }
+
+    #[test]
+    fn bad_padding() {
+        assert_eq!(base64_decode("Zg="), Err(Base64Error::InvalidLength(3)));
+        assert_eq!(base64_decode("Zg"), Err(Base64Error::InvalidLength(2)));
+        assert_eq!(base64_decode("Z==="), Err(Base64Error::InvalidPadding));
+        assert_eq!(base64_decode("===="), Err(Base64Error::InvalidPadding));
+        assert_eq!(base64_decode("Zm=v"), Err(Base64Error::InvalidPadding));
+        assert_eq!(base64_decode("Zg==Zg=="), Err(Base64Error::InvalidPadding));
+        assert!(base64_decode_variant("Zg==", Base64Variant::StandardNoPad).is_err());
+    }
+
+    #[test]
+    fn non_canonical_bits() {
+        assert_eq!(base64_decode("Zh=="), Err(Base64Error::NonCanonical));
+        assert_eq!(base64_decode("Zm9="), Err(Base64Error::NonCanonical));
+        assert_eq!(base64_decode_variant("Zh", Base64Variant::StandardNoPad), Err(Base64Error::NonCanonical));
+    }
+
+    #[test]
+    fn invalid_characters() {
+        assert_eq!(base64_decode("Zm9v!A=="), Err(Base64Error::InvalidCharacter { character: '!', position: 4 }));
+        assert!(matches!(base64_decode("Zm9v\n"), Err(Base64Error::InvalidLength(_))));
+        assert!(base64_decode("Zm9v Zm9v").is_err());
+        assert!(base64_decode("Zm9vé").is_err());
+    }
+
+    #[test]
+    fn streaming_encoder() {
+        let data: Vec<u8> = (0..10007u32).map(|i| (i * 31 + 7) as u8).collect();
+        for step in [1, 2, 3, 5, 64, 1000] {
+            let mut encoder = Base64Encoder::new(Vec::new(), Base64Variant::Standard);
+            for chunk in data.chunks(step) {
+                encoder.write_all(chunk).unwrap();
+            }
+            let output = encoder.finish().unwrap();
+            assert_eq!(String::from_utf8(output).unwrap(), base64_encode(&data));
+        }
+        assert_eq!(base64_decode(&base64_encode(&data)).unwrap(), data);
+    }
+}
@@ -1,5 +1,8 @@
-const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
-const CHARSET_REV: [i8; 128] = [
+use std::collections::HashMap;
+use crate::error::Error;
+
+pub(crate) const CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+pub(crate) const CHARSET_REV: [i8; 128] = [
     -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
     -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
     -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1, -1,
@@ -10,28 +13,46 @@ const CHARSET_REV: [i8; 128] = [
      1,  0,  3, 16, 11, 28, 12, 14,  6,  4,  2, -1, -1, -1, -1, -1
 ];
 
+pub const BECH32_MAX_LENGTH: usize = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Bech32Variant {
     Bech32,
     Bech32m,
 }
 
-fn polymod(values: &[u8]) -> u32 {
-    let mut chk: u32 = 1;
-    for v in values {
-        let top = chk >> 25;
-        chk = ((chk & 0x1ffffff) << 5) ^ (*v as u32);
-        for (i, &gen) in [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3]
-            .iter()
-            .enumerate()
-        {
-            if (top >> i) & 1 != 0 {
-                chk ^= gen;
-            }
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bech32Correction {
+    pub variant: Bech32Variant,
+    pub positions: Vec<usize>,
+    pub corrected: String,
+}
+
+fn variant_constant(variant: Bech32Variant) -> u32 {
+    match variant {
+        Bech32Variant::Bech32 => 1,
+        Bech32Variant::Bech32m => 0x2bc830a3,
+    }
+}
+
+fn polymod_step(chk: u32, v: u8) -> u32 {
+    let top = chk >> 25;
+    let mut chk = ((chk & 0x1ffffff) << 5) ^ (v as u32);
+    for (i, &gen) in [0x3b6a57b2, 0x26508e6d, 0x1ea119fa, 0x3d4233dd, 0x2a1462b3]
+        .iter()
+        .enumerate()
+    {
+        if (top >> i) & 1 != 0 {
+            chk ^= gen;
         }
     }
     chk
 }
 
+fn polymod(values: &[u8]) -> u32 {
+    values.iter().fold(1, |chk, &v| polymod_step(chk, v))
+}
+
 fn hrp_expand(hrp: &str) -> Vec<u8> {
     let mut ret = Vec::new();
     for b in hrp.bytes() {
@@ -48,11 +69,7 @@ fn create_checksum(hrp: &str, data: &[u8], variant: Bech32Variant) -> Vec<u8> {
     let mut values = hrp_expand(hrp);
     values.extend_from_slice(data);
     values.extend_from_slice(&[0u8; 6]);
-    let polymod_result = polymod(&values)
-        ^ match variant {
-            Bech32Variant::Bech32 => 1,
-            Bech32Variant::Bech32m => 0x2bc830a3,
-        };
+    let polymod_result = polymod(&values) ^ variant_constant(variant);
     let mut checksum = Vec::new();
     for i in 0..6 {
         checksum.push(((polymod_result >> (5 * (5 - i))) & 31) as u8);
@@ -60,13 +77,23 @@ fn create_checksum(hrp: &str, data: &[u8], variant: Bech32Variant) -> Vec<u8> {
     checksum
 }
 
-fn verify_checksum(hrp: &str, data: &[u8]) -> bool {
+fn verify_checksum(hrp: &str, data: &[u8]) -> Option<Bech32Variant> {
     let mut values = hrp_expand(hrp);
     values.extend_from_slice(data);
-    polymod(&values) == 1
+    [Bech32Variant::Bech32, Bech32Variant::Bech32m]
+        .into_iter()
+        .find(|&variant| polymod(&values) == variant_constant(variant))
 }
 
-pub fn encode_bech32(hrp: &str, data: &[u8], variant: Bech32Variant) -> String {
+pub fn encode_bech32(hrp: &str, data: &[u8], variant: Bech32Variant) -> Result<String, Error> {
+    if hrp.is_empty() || hrp.len() > 83 || hrp.bytes().any(|b| !(33..=126).contains(&b)) {
+        return Err(Error::Bech32("Invalid HRP"));
+    }
+    if data.iter().any(|&d| d > 31) {
+        return Err(Error::Bech32("Invalid data range: value exceeds 5 bits"));
+    }
+
+    let hrp = &hrp.to_ascii_lowercase();
     let checksum = create_checksum(hrp, data, variant);
     let mut combined = Vec::new();
     combined.extend_from_slice(data);
@@ -75,12 +102,16 @@ pub fn encode_bech32(hrp: &str, data: &[u8], variant: Bech32Variant) -> String {
     let mut result = hrp.to_string();
     result.push('1');
     for d in combined {
-        result.push(CHARSET.chars().nth(d as usize).unwrap());
+        result.push(CHARSET.as_bytes()[d as usize] as char);
     }
-    result
+    Ok(result)
+}
+
+pub fn encode_bech32_upper(hrp: &str, data: &[u8], variant: Bech32Variant) -> Result<String, Error> {
+    Ok(encode_bech32(hrp, data, variant)?.to_ascii_uppercase())
 }
 
-pub fn convert_bits(data: &[u8], from_bits: u32, to_bits: u32, pad: bool) -> Result<Vec<u8>, &'static str> {
+pub fn convert_bits(data: &[u8], from_bits: u32, to_bits: u32, pad: bool) -> Result<Vec<u8>, Error> {
     let mut acc: u32 = 0;
     let mut bits: u32 = 0;
     let mut ret: Vec<u8> = Vec::new();
@@ -88,7 +119,7 @@ pub fn convert_bits(data: &[u8], from_bits: u32, to_bits: u32, pad: bool) -> Res
     for value in data {
         let v = *value as u32;
         if (v >> from_bits) != 0 {
-            return Err("Invalid data range: value exceeds from_bits size");
+            return Err(Error::Bech32("Invalid data range: value exceeds from_bits size"));
         }
         acc = (acc << from_bits) | v;
         bits += from_bits;
@@ -102,36 +133,361 @@ pub fn convert_bits(data: &[u8], from_bits: u32, to_bits: u32, pad: bool) -> Res
             ret.push(((acc << (to_bits - bits)) & maxv) as u8);
         }
     } else if bits >= from_bits || ((acc << (to_bits - bits)) & maxv) != 0 {
-        return Err("Invalid padding");
+        return Err(Error::Bech32("Invalid padding"));
     }
     Ok(ret)
 }
 
-pub fn decode_bech32(bech32: &str) -> Result<(String, Vec<u8>), &'static str> {
-    if bech32.len() < 8 || bech32.len() > 90 {
-        return Err("Invalid Bech32 string length");
+pub fn decode_bech32(bech32: &str) -> Result<(String, Vec<u8>, Bech32Variant), Error> {
+    decode_bech32_with_limit(bech32, BECH32_MAX_LENGTH)
+}
+
+pub fn decode_bech32_with_limit(bech32: &str, max_length: usize) -> Result<(String, Vec<u8>, Bech32Variant), Error> {
+    if bech32.len() < 8 || bech32.len() > max_length {
+        return Err(Error::Bech32("Invalid Bech32 string length"));
     }
 
+    if bech32.bytes().any(|b| b.is_ascii_lowercase()) && bech32.bytes().any(|b| b.is_ascii_uppercase()) {
+        return Err(Error::Bech32("Invalid Bech32 string: mixed case"));
+    }
+    let bech32 = &bech32.to_ascii_lowercase();
+
     let (hrp, data) = match bech32.rfind('1') {
         Some(pos) => (&bech32[..pos], &bech32[pos + 1..]),
-        None => return Err("Invalid Bech32 format: missing separator '1'"),
+        None => return Err(Error::Bech32("Invalid Bech32 format: missing separator '1'")),
     };
 
     if hrp.is_empty() || data.len() < 6 {
-        return Err("Invalid Bech32 format: HRP or data part too short");
+        return Err(Error::Bech32("Invalid Bech32 format: HRP or data part too short"));
+    }
+
+    if hrp.len() > 83 || hrp.bytes().any(|b| !(33..=126).contains(&b)) {
+        return Err(Error::Bech32("Invalid character in Bech32 HRP"));
     }
 
     let mut data_values = Vec::with_capacity(data.len());
     for c in data.chars() {
         if c as usize >= 128 || CHARSET_REV[c as usize] == -1 {
-            return Err("Invalid character in Bech32 string");
+            return Err(Error::Bech32("Invalid character in Bech32 string"));
         }
         data_values.push(CHARSET_REV[c as usize] as u8);
     }
 
-    if !verify_checksum(hrp, &data_values) {
-        return Err("Invalid checksum");
+    let variant = verify_checksum(hrp, &data_values).ok_or(Error::Bech32("Invalid checksum"))?;
+
+    Ok((hrp.to_string(), data_values[..data_values.len() - 6].to_vec(), variant))
+}
+
+// The checksum is linear over GF(2): flipping value bits at a data position changes the
+// polymod result by a fixed syndrome, so errors are located by matching syndromes.
+// The code has distance 5, which makes any solution with at most two errors unique.
+fn solve_errors(target: u32, syndromes: &[[u32; 5]], erasures: &[usize]) -> Option<Vec<(usize, u8)>> {
+    let syndrome = |k: usize, d: u8| (0..5).filter(|b| (d >> b) & 1 == 1).fold(0, |acc, b| acc ^ syndromes[k][b]);
+
+    let mut singles = HashMap::new();
+    for k in (0..syndromes.len()).filter(|k| !erasures.contains(k)) {
+        for d in 1..32u8 {
+            singles.insert(syndrome(k, d), (k, d));
+        }
+    }
+
+    match erasures {
+        [] => {
+            if let Some(&single) = singles.get(&target) {
+                return Some(vec![single]);
+            }
+            for (&s, &(k, d)) in &singles {
+                if let Some(&(k2, d2)) = singles.get(&(target ^ s)) {
+                    if k2 != k {
+                        let mut errors = vec![(k, d), (k2, d2)];
+                        errors.sort();
+                        return Some(errors);
+                    }
+                }
+            }
+            None
+        }
+        [e] => {
+            if let Some(d) = (0..32u8).find(|&d| syndrome(*e, d) == target) {
+                return Some(vec![(*e, d)]);
+            }
+            for d in 0..32u8 {
+                if let Some(&(k, d2)) = singles.get(&(target ^ syndrome(*e, d))) {
+                    let mut errors = vec![(*e, d), (k, d2)];
+                    errors.sort();
+                    return Some(errors);
+                }
+            }
+            None
+        }
+        [e1, e2] => {
+            for d1 in 0..32u8 {
+                for d2 in 0..32u8 {
+                    if syndrome(*e1, d1) ^ syndrome(*e2, d2) == target {
+                        return Some(vec![(*e1, d1), (*e2, d2)]);
+                    }
+                }
+            }
+            None
+        }
+        _ => None,
+    }
+}
+
+pub fn locate_bech32_errors(bech32: &str) -> Vec<Bech32Correction> {
+    let chars: Vec<char> = bech32.chars().collect();
+    if chars.len() < 8 || chars.len() > BECH32_MAX_LENGTH {
+        return Vec::new();
+    }
+
+    let separator = match chars.iter().rposition(|&c| c == '1') {
+        Some(pos) => pos,
+        None => return Vec::new(),
+    };
+    let hrp: String = chars[..separator].iter().collect::<String>().to_lowercase();
+    let data = &chars[separator + 1..];
+    if hrp.is_empty() || data.len() < 6 {
+        return Vec::new();
+    }
+
+    let mut values = Vec::with_capacity(data.len());
+    let mut erasures = Vec::new();
+    for (k, &c) in data.iter().enumerate() {
+        if c.is_ascii() && CHARSET_REV[c as usize] != -1 {
+            values.push(CHARSET_REV[c as usize] as u8);
+        } else {
+            values.push(0);
+            erasures.push(k);
+        }
+    }
+    if erasures.len() > 2 {
+        return Vec::new();
+    }
+
+    let uppercase = chars.iter().any(|c| c.is_ascii_uppercase());
+
+    let mut syndromes = vec![[0u32; 5]; values.len()];
+    let mut current = [1u32, 2, 4, 8, 16];
+    for syndrome in syndromes.iter_mut().rev() {
+        *syndrome = current;
+        for c in current.iter_mut() {
+            *c = polymod_step(*c, 0);
+        }
+    }
+
+    let mut expanded = hrp_expand(&hrp);
+    expanded.extend_from_slice(&values);
+    let residue = polymod(&expanded);
+
+    let mut candidates: Vec<Bech32Correction> = Vec::new();
+    for variant in [Bech32Variant::Bech32, Bech32Variant::Bech32m] {
+        let target = residue ^ variant_constant(variant);
+        if target == 0 && erasures.is_empty() {
+            return Vec::new();
+        }
+
+        let errors = match solve_errors(target, &syndromes, &erasures) {
+            Some(errors) => errors,
+            None => continue,
+        };
+
+        let mut corrected = chars.clone();
+        for &(k, d) in &errors {
+            let c = CHARSET.as_bytes()[(values[k] ^ d) as usize] as char;
+            corrected[separator + 1 + k] = if uppercase { c.to_ascii_uppercase() } else { c };
+        }
+
+        candidates.push(Bech32Correction {
+            variant,
+            positions: errors.iter().map(|&(k, _)| separator + 1 + k).collect(),
+            corrected: corrected.into_iter().collect(),
+        });
+    }
+
+    if let Some(min) = candidates.iter().map(|c| c.positions.len()).min() {
+        candidates.retain(|c| c.positions.len() == min);
+    }
+    candidates
+}
+
+pub fn suggest_bech32_correction(bech32: &str) -> Option<String> {
+    match locate_bech32_errors(bech32).as_slice() {
+        [candidate] => Some(candidate.corrected.clone()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const P2WPKH: &str = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+    const P2TR: &str = "bc1p0xlxvlhemja6c4dqv22uapctqupfhlxm9h8z3k2e72q4k9hcz7vqzk5jj0";
+
+    fn replace_at(s: &str, position: usize, c: char) -> String {
+        s.chars().enumerate().map(|(i, x)| if i == position { c } else { x }).collect()
+    }
+
+    #[test]
+    fn locate_valid_string() {
+        assert!(locate_bech32_errors(P2WPKH).is_empty());
+        assert!(locate_bech32_errors(P2TR).is_empty());
+        assert_eq!(suggest_bech32_correction(P2WPKH), None);
+    }
+
+    #[test]
+    fn locate_single_substitution() {
+        for (address, variant) in [(P2WPKH, Bech32Variant::Bech32), (P2TR, Bech32Variant::Bech32m)] {
+            let typo = replace_at(address, 10, if address.as_bytes()[10] == b'q' { 'p' } else { 'q' });
+            let candidates = locate_bech32_errors(&typo);
+            assert_eq!(candidates.len(), 1);
+            assert_eq!(candidates[0].variant, variant);
+            assert_eq!(candidates[0].positions, [10]);
+            assert_eq!(suggest_bech32_correction(&typo).as_deref(), Some(address));
+        }
+    }
+
+    #[test]
+    fn locate_double_substitution() {
+        let typo = replace_at(&replace_at(P2WPKH, 5, 'x'), 30, 'z');
+        let candidates = locate_bech32_errors(&typo);
+        assert!(candidates.iter().any(|c| c.corrected == P2WPKH && c.positions == [5, 30]));
     }
 
-    Ok((hrp.to_string(), data_values[..data_values.len() - 6].to_vec()))
-}
\ No newline at end of file
+    #[test]
+    fn locate_erasures() {
+        let typo = replace_at(&replace_at(P2TR, 12, 'o'), 40, 'b');
+        let candidates = locate_bech32_errors(&typo);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].positions, [12, 40]);
+        assert_eq!(candidates[0].corrected, P2TR);
+
+        let too_many = replace_at(&typo, 20, 'i');
+        assert!(locate_bech32_errors(&too_many).is_empty());
+    }
+
+    #[test]
+    fn locate_preserves_case() {
+        let typo = replace_at(P2WPKH, 10, 'p').to_uppercase();
+        let upper = P2WPKH.to_uppercase();
+        assert_eq!(suggest_bech32_correction(&typo).as_deref(), Some(upper.as_str()));
+    }
+
+    // BIP173 valid Bech32 strings.
+    #[test]
+    fn bip173_valid_strings() {
+        let long = format!("11{}c8247j", "q".repeat(82));
+        let valid = [
+            "A12UEL5L",
+            "a12uel5l",
+            "an83characterlonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1tt5tgs",
+            "abcdef1qpzry9x8gf2tvdw0s3jn54khce6mua7lmqqqxw",
+            &long,
+            "split1checkupstagehandshakeupstreamerranterredcaperred2y9e3w",
+            "?1ezyfcl",
+        ];
+        for bech32 in valid {
+            let (hrp, data, variant) = decode_bech32(bech32).unwrap();
+            assert_eq!(variant, Bech32Variant::Bech32);
+            assert_eq!(encode_bech32(&hrp, &data, variant).unwrap(), bech32.to_lowercase());
+        }
+    }
+
+    // BIP350 valid Bech32m strings.
+    #[test]
+    fn bip350_valid_strings() {
+        let long = format!("11{}udsr8", "l".repeat(83));
+        let valid = [
+            "A1LQFN3A",
+            "a1lqfn3a",
+            "an83characterlonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11sg7hg6",
+            "abcdef1l7aum6echk45nj3s0wdvt2fg8x9yrzpqzd3ryx",
+            &long,
+            "split1checkupstagehandshakeupstreamerranterredcaperredlc445v",
+            "?1v759aa",
+        ];
+        for bech32 in valid {
+            let (hrp, data, variant) = decode_bech32(bech32).unwrap();
+            assert_eq!(variant, Bech32Variant::Bech32m);
+            assert_eq!(encode_bech32(&hrp, &data, variant).unwrap(), bech32.to_lowercase());
+        }
+    }
+
+    // BIP173 and BIP350 invalid strings.
+    #[test]
+    fn invalid_strings() {
+        let invalid = [
+            "\x201nwldj5",
+            "\x7f1axkwrx",
+            "\u{80}1eym55h",
+            "an84characterslonghumanreadablepartthatcontainsthenumber1andtheexcludedcharactersbio1569pvx",
+            "pzry9x0s0muk",
+            "1pzry9x0s0muk",
+            "x1b4n0q5v",
+            "li1dgmt3",
+            "de1lg7wt\u{ff}",
+            "A1G7SGD8",
+            "10a06t8",
+            "1qzzfhee",
+            "\x201xj0phk",
+            "\x7f1g6xzxy",
+            "\u{80}1vctc34",
+            "an84characterslonghumanreadablepartthatcontainsthetheexcludedcharactersbioandnumber11d6pts4",
+            "qyrz8wqd2c9m",
+            "1qyrz8wqd2c9m",
+            "y1b0jsk6g",
+            "lt1igcx5c0",
+            "in1muywd",
+            "mm1crxm3i",
+            "au1s5cgom",
+            "M1VUXWEZ",
+            "16plkw9",
+            "1p2gdwpf",
+        ];
+        for bech32 in invalid {
+            assert!(decode_bech32(bech32).is_err(), "{:?}", bech32);
+        }
+    }
+
+    #[test]
+    fn mixed_case_rejected() {
+        assert!(decode_bech32("a12UEL5L").is_err());
+        assert!(decode_bech32("A12uEL5L").is_err());
+        assert!(decode_bech32("A12UEL5L").is_ok());
+    }
+
+    #[test]
+    fn encode_upper_round_trip() {
+        assert_eq!(encode_bech32_upper("a", &[], Bech32Variant::Bech32).unwrap(), "A12UEL5L");
+        assert_eq!(encode_bech32_upper("a", &[], Bech32Variant::Bech32m).unwrap(), "A1LQFN3A");
+
+        let data: Vec<u8> = (0..32).collect();
+        for variant in [Bech32Variant::Bech32, Bech32Variant::Bech32m] {
+            let upper = encode_bech32_upper("test", &data, variant).unwrap();
+            assert_eq!(upper, upper.to_uppercase());
+            assert_eq!(decode_bech32(&upper).unwrap(), ("test".to_string(), data.clone(), variant));
+        }
+    }
+
+    #[test]
+    fn encode_rejects_invalid_input() {
+        assert!(encode_bech32("", &[0], Bech32Variant::Bech32).is_err());
+        assert!(encode_bech32("a b", &[0], Bech32Variant::Bech32).is_err());
+        assert!(encode_bech32(&"a".repeat(84), &[0], Bech32Variant::Bech32).is_err());
+        assert!(encode_bech32("a", &[32], Bech32Variant::Bech32).is_err());
+    }
+
+    #[test]
+    fn length_limit() {
+        let long = encode_bech32("lnbc", &[0; 100], Bech32Variant::Bech32).unwrap();
+        assert!(decode_bech32(&long).is_err());
+        assert_eq!(decode_bech32_with_limit(&long, 1023).unwrap().1, [0; 100]);
+    }
+
+    #[test]
+    fn convert_bits_padding() {
+        assert_eq!(convert_bits(&[0xff], 8, 5, true).unwrap(), [31, 28]);
+        assert!(convert_bits(&[31, 29], 5, 8, false).is_err());
+        assert_eq!(convert_bits(&[31, 28], 5, 8, false).unwrap(), [0xff]);
+    }
+}
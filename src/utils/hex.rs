@@ -1,9 +1,140 @@
+use std::fmt;
+
+const HEX_CHARS: &[u8; 16] = b"0123456789abcdef";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HexError {
+    OddLength(usize),
+    InvalidCharacter { character: char, position: usize },
+    InvalidLength { expected: usize, found: usize },
+}
+
+impl fmt::Display for HexError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HexError::OddLength(len) => write!(f, "Odd hex string length: {}", len),
+            HexError::InvalidCharacter { character, position } => {
+                write!(f, "Invalid hex character '{}' at position {}", character, position)
+            }
+            HexError::InvalidLength { expected, found } => {
+                write!(f, "Invalid length: expected {} bytes, found {}", expected, found)
+            }
+        }
+    }
+}
+
+impl std::error::Error for HexError {}
+
+pub trait ToHex {
+    fn to_hex(&self) -> String;
+}
+
+pub trait FromHex: Sized {
+    type Error;
+
+    fn from_hex(hex: &str) -> Result<Self, Self::Error>;
+}
+
+fn nibble(hex: &str, position: usize) -> Result<u8, HexError> {
+    match hex.as_bytes()[position] {
+        c @ b'0'..=b'9' => Ok(c - b'0'),
+        c @ b'a'..=b'f' => Ok(c - b'a' + 10),
+        c @ b'A'..=b'F' => Ok(c - b'A' + 10),
+        _ => {
+            let start = (0..=position).rev().find(|&i| hex.is_char_boundary(i)).unwrap_or(0);
+            let character = hex[start..].chars().next().unwrap_or('\u{FFFD}');
+            Err(HexError::InvalidCharacter { character, position: start })
+        }
+    }
+}
+
 pub fn vec_to_hex(bytes: &[u8]) -> String {
-    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    let mut hex = String::with_capacity(bytes.len() * 2);
+    for byte in bytes {
+        hex.push(HEX_CHARS[(byte >> 4) as usize] as char);
+        hex.push(HEX_CHARS[(byte & 0x0F) as usize] as char);
+    }
+    hex
+}
+
+pub fn hex_to_vec(hex: &str) -> Result<Vec<u8>, HexError> {
+    if !hex.len().is_multiple_of(2) {
+        return Err(HexError::OddLength(hex.len()));
+    }
+
+    let mut bytes = Vec::with_capacity(hex.len() / 2);
+    for i in (0..hex.len()).step_by(2) {
+        bytes.push(nibble(hex, i)? << 4 | nibble(hex, i + 1)?);
+    }
+    Ok(bytes)
 }
 
-pub fn hex_to_vec(hex: &str) -> Vec<u8> {
-    hex.as_bytes().chunks(2).map(|chunk| {
-        u8::from_str_radix(std::str::from_utf8(chunk).unwrap(), 16).unwrap()
-    }).collect()
-}
\ No newline at end of file
+impl ToHex for [u8] {
+    fn to_hex(&self) -> String {
+        vec_to_hex(self)
+    }
+}
+
+impl ToHex for Vec<u8> {
+    fn to_hex(&self) -> String {
+        vec_to_hex(self)
+    }
+}
+
+impl<const N: usize> ToHex for [u8; N] {
+    fn to_hex(&self) -> String {
+        vec_to_hex(self)
+    }
+}
+
+impl FromHex for Vec<u8> {
+    type Error = HexError;
+
+    fn from_hex(hex: &str) -> Result<Self, HexError> {
+        hex_to_vec(hex)
+    }
+}
+
+impl<const N: usize> FromHex for [u8; N] {
+    type Error = HexError;
+
+    fn from_hex(hex: &str) -> Result<Self, HexError> {
+        let bytes = hex_to_vec(hex)?;
+        let found = bytes.len();
+        bytes.try_into().map_err(|_| HexError::InvalidLength { expected: N, found })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_round_trip() {
+        assert_eq!(hex_to_vec("00ffAb").unwrap(), [0x00, 0xff, 0xab]);
+        assert_eq!(vec_to_hex(&[0x00, 0xff, 0xab]), "00ffab");
+        assert_eq!(hex_to_vec("").unwrap(), Vec::<u8>::new());
+        assert_eq!([0xdeu8, 0xad].to_hex(), "dead");
+        assert_eq!(Vec::<u8>::from_hex("dead").unwrap().to_hex(), "dead");
+    }
+
+    #[test]
+    fn odd_length() {
+        assert_eq!(hex_to_vec("abc"), Err(HexError::OddLength(3)));
+        assert_eq!(Vec::<u8>::from_hex("0"), Err(HexError::OddLength(1)));
+    }
+
+    #[test]
+    fn invalid_character() {
+        assert_eq!(hex_to_vec("zz"), Err(HexError::InvalidCharacter { character: 'z', position: 0 }));
+        assert_eq!(hex_to_vec("+1"), Err(HexError::InvalidCharacter { character: '+', position: 0 }));
+        assert_eq!(hex_to_vec("0é0"), Err(HexError::InvalidCharacter { character: 'é', position: 1 }));
+    }
+
+    #[test]
+    fn fixed_length_arrays() {
+        assert_eq!(<[u8; 2]>::from_hex("0102").unwrap(), [1, 2]);
+        assert_eq!(<[u8; 3]>::from_hex("0102"), Err(HexError::InvalidLength { expected: 3, found: 2 }));
+        assert_eq!(<[u8; 1]>::from_hex("0102"), Err(HexError::InvalidLength { expected: 1, found: 2 }));
+    }
+}
@@ -5,12 +5,8 @@ pub mod bech32;
 pub mod varint;
 
 
-pub use base58::base58_encode;
-pub use base64::base64_encode;
-pub use base64::base64_decode;
-pub use hex::vec_to_hex;
-pub use hex::hex_to_vec;
-pub use bech32::encode_bech32;
-pub use bech32::decode_bech32;
-pub use varint::write_varint;
-pub use varint::read_varint;
\ No newline at end of file
+pub use base58::{base58_encode, base58_decode, base58check_encode, base58check_decode, Base58Error};
+pub use base64::{base64_encode, base64_decode, base64_encode_variant, base64_decode_variant, Base64Variant, Base64Error, Base64Encoder};
+pub use hex::{vec_to_hex, hex_to_vec, ToHex, FromHex, HexError};
+pub use bech32::{encode_bech32, encode_bech32_upper, decode_bech32, decode_bech32_with_limit, locate_bech32_errors, suggest_bech32_correction, Bech32Variant, Bech32Correction};
+pub use varint::{write_varint, read_varint, CoreVarInt};
\ No newline at end of file
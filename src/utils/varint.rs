@@ -1,59 +1,112 @@
+use std::io::{self, Read, Write};
+
+use crate::consensus::{Encodable, Decodable, EncodeError, VarInt};
+use crate::error::Error;
+
 pub fn write_varint(value: usize, buffer: &mut Vec<u8>) {
-    if value < 0xFD {
-        buffer.push(value as u8);
-    } else if value <= 0xFFFF {
-        buffer.push(0xFD);
-        buffer.push((value & 0xFF) as u8);
-        buffer.push((value >> 8) as u8);
-    } else if value <= 0xFFFFFFFF {
-        buffer.push(0xFE);
-        buffer.push((value & 0xFF) as u8);
-        buffer.push(((value >> 8) & 0xFF) as u8);
-        buffer.push(((value >> 16) & 0xFF) as u8);
-        buffer.push(((value >> 24) & 0xFF) as u8);
-    } else {
-        buffer.push(0xFF);
-        buffer.push((value & 0xFF) as u8);
-        buffer.push(((value >> 8) & 0xFF) as u8);
-        buffer.push(((value >> 16) & 0xFF) as u8);
-        buffer.push(((value >> 24) & 0xFF) as u8);
-        buffer.push(((value >> 32) & 0xFF) as u8);
-        buffer.push(((value >> 40) & 0xFF) as u8);
-        buffer.push(((value >> 48) & 0xFF) as u8);
-        buffer.push(((value >> 56) & 0xFF) as u8);
+    VarInt(value as u64).consensus_encode(buffer).expect("Writing to a Vec never fails");
+}
+
+pub fn read_varint(data: &[u8], index: &mut usize) -> Result<usize, Error> {
+    let mut reader = data.get(*index..).ok_or(EncodeError::ParseFailed("Varint index out of bounds"))?;
+    let remaining = reader.len();
+
+    let value = VarInt::consensus_decode(&mut reader)?;
+    *index += remaining - reader.len();
+    Ok(usize::try_from(value.0).map_err(|_| EncodeError::ParseFailed("Varint too large"))?)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CoreVarInt(pub u64);
+
+impl Encodable for CoreVarInt {
+    fn consensus_encode<W: Write + ?Sized>(&self, writer: &mut W) -> io::Result<usize> {
+        let mut tmp = [0u8; 10];
+        let mut n = self.0;
+        let mut len = 0;
+        loop {
+            tmp[len] = (n & 0x7F) as u8 | if len > 0 { 0x80 } else { 0x00 };
+            if n <= 0x7F {
+                break;
+            }
+            n = (n >> 7) - 1;
+            len += 1;
+        }
+
+        tmp[..=len].reverse();
+        writer.write_all(&tmp[..=len])?;
+        Ok(len + 1)
     }
 }
 
-pub fn read_varint(data: &[u8], index: &mut usize) -> usize {
-    let first = data[*index];
-    *index += 1;
+impl Decodable for CoreVarInt {
+    fn consensus_decode<R: Read + ?Sized>(reader: &mut R) -> Result<Self, EncodeError> {
+        let mut n: u64 = 0;
+        loop {
+            let byte = u8::consensus_decode(reader)?;
+            if n > (u64::MAX >> 7) {
+                return Err(EncodeError::ParseFailed("VARINT overflow"));
+            }
+            n = (n << 7) | (byte & 0x7F) as u64;
+            if byte & 0x80 == 0 {
+                return Ok(CoreVarInt(n));
+            }
+            if n == u64::MAX {
+                return Err(EncodeError::ParseFailed("VARINT overflow"));
+            }
+            n += 1;
+        }
+    }
+}
 
-    match first {
-        0xFD => {
-            let val = (data[*index] as u16) | ((data[*index + 1] as u16) << 8);
-            *index += 2;
-            val as usize
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::{deserialize, serialize};
+    use crate::utils::{hex_to_vec, vec_to_hex};
+
+    #[test]
+    fn compact_size_round_trip() {
+        let mut buffer = Vec::new();
+        for value in [0, 0xfc, 0xfd, 0x10000] {
+            write_varint(value, &mut buffer);
         }
-        0xFE => {
-            let val = (data[*index] as u32)
-                | ((data[*index + 1] as u32) << 8)
-                | ((data[*index + 2] as u32) << 16)
-                | ((data[*index + 3] as u32) << 24);
-            *index += 4;
-            val as usize
+        assert_eq!(vec_to_hex(&buffer), "00fcfdfd00fe00000100");
+
+        let mut index = 0;
+        for value in [0, 0xfc, 0xfd, 0x10000] {
+            assert_eq!(read_varint(&buffer, &mut index).unwrap(), value);
         }
-        0xFF => {
-            let val = (data[*index] as u64)
-                | ((data[*index + 1] as u64) << 8)
-                | ((data[*index + 2] as u64) << 16)
-                | ((data[*index + 3] as u64) << 24)
-                | ((data[*index + 4] as u64) << 32)
-                | ((data[*index + 5] as u64) << 40)
-                | ((data[*index + 6] as u64) << 48)
-                | ((data[*index + 7] as u64) << 56);
-            *index += 8;
-            val as usize
+        assert_eq!(index, buffer.len());
+        assert!(read_varint(&buffer, &mut index).is_err());
+        assert!(read_varint(&buffer, &mut 100).is_err());
+    }
+
+    // Bitcoin Core serialize_tests varint vectors.
+    #[test]
+    fn core_varint_vectors() {
+        let cases = [
+            (0, "00"),
+            (0x7f, "7f"),
+            (0x80, "8000"),
+            (0x1234, "a334"),
+            (0xffff, "82fe7f"),
+            (0x123456, "c7e756"),
+            (0x80123456, "86ffc7e756"),
+            (0xffffffff, "8efefefe7f"),
+            (0x7fffffffffffffff, "fefefefefefefefe7f"),
+            (0xffffffffffffffff, "80fefefefefefefefe7f"),
+        ];
+        for (value, hex) in cases {
+            assert_eq!(vec_to_hex(&serialize(&CoreVarInt(value))), hex);
+            assert_eq!(deserialize::<CoreVarInt>(&hex_to_vec(hex).unwrap()).unwrap(), CoreVarInt(value));
         }
-        _ => first as usize,
+    }
+
+    #[test]
+    fn core_varint_overflow() {
+        assert!(deserialize::<CoreVarInt>(&hex_to_vec("80fefefefefefefefeff7f").unwrap()).is_err());
+        assert!(deserialize::<CoreVarInt>(&hex_to_vec("80fefefefefefefeff00").unwrap()).is_err());
+        assert!(deserialize::<CoreVarInt>(&hex_to_vec("80").unwrap()).is_err());
     }
 }